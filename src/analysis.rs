@@ -0,0 +1,337 @@
+use std::fmt;
+
+use crate::card;
+use crate::rank;
+use crate::rules;
+use crate::suit;
+
+pub const TEN: u32 = 10;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Composition
+{
+    counts: [u32; 10],
+}
+
+impl Composition
+{
+    pub fn with_decks(num_decks: u32) -> Composition
+    {
+        let mut counts = [4 * num_decks; 10];
+        counts[TEN as usize - 1] = 16 * num_decks;
+        Composition { counts }
+    }
+
+    pub fn from_cards(cards: &[card::Card]) -> Composition
+    {
+        let mut composition = Composition { counts: [0; 10] };
+        for card in cards
+        {
+            composition.counts[card.get_point_value() as usize - 1] += 1;
+        }
+        composition
+    }
+
+    pub fn count(&self, value: u32) -> u32
+    {
+        self.counts[value as usize - 1]
+    }
+
+    pub fn len(&self) -> u32
+    {
+        self.counts.iter().sum()
+    }
+
+    pub fn is_empty(&self) -> bool
+    {
+        self.len() == 0
+    }
+
+    pub fn probability(&self, value: u32) -> f64
+    {
+        match self.len()
+        {
+            0 => 0.0,
+            len => self.count(value) as f64 / len as f64,
+        }
+    }
+
+    pub fn remove(&mut self, card: &card::Card) -> bool
+    {
+        self.remove_value(card.get_point_value())
+    }
+
+    pub fn remove_value(&mut self, value: u32) -> bool
+    {
+        match self.count(value)
+        {
+            0 => false,
+            _ => {
+                self.counts[value as usize - 1] -= 1;
+                true
+            }
+        }
+    }
+
+    pub fn add_value(&mut self, value: u32)
+    {
+        self.counts[value as usize - 1] += 1;
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DealerOutcomes
+{
+    pub totals: [f64; 5],
+    pub blackjack: f64,
+    pub bust: f64,
+}
+
+impl DealerOutcomes
+{
+    pub fn total(&self, total: u32) -> f64
+    {
+        match total
+        {
+            17..=21 => self.totals[total as usize - 17],
+            _ => 0.0,
+        }
+    }
+
+    pub fn sum(&self) -> f64
+    {
+        self.totals.iter().sum::<f64>() + self.blackjack + self.bust
+    }
+}
+
+impl fmt::Display for DealerOutcomes
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        for (index, probability) in self.totals.iter().enumerate()
+        {
+            write!(f, "{}: {:.4}%, ", index + 17, probability * 100.0)?;
+        }
+        write!(f, "Blackjack: {:.4}%, Bust: {:.4}%", self.blackjack * 100.0, self.bust * 100.0)
+    }
+}
+
+pub fn point_value(rank: rank::Rank) -> u32
+{
+    card::Card { rank, suit: suit::Suit::Spades }.get_point_value()
+}
+
+// Aces are kept at one point and promoted to eleven the same way Hand::value does it.
+pub fn best_total(hard_total: u32, has_ace: bool) -> u32
+{
+    match has_ace && hard_total + 10 <= 21
+    {
+        true => hard_total + 10,
+        false => hard_total,
+    }
+}
+
+pub fn blackjack_hole_card(up_value: u32) -> Option<u32>
+{
+    match up_value
+    {
+        1 => Some(TEN),
+        TEN => Some(1),
+        _ => None,
+    }
+}
+
+fn dealer_stands(hard_total: u32, has_ace: bool, hits_soft_17: bool) -> bool
+{
+    let total = best_total(hard_total, has_ace);
+    let soft = total != hard_total;
+    total > 17 || (total == 17 && !(hits_soft_17 && soft))
+}
+
+fn dealer_draw(hard_total: u32, has_ace: bool, num_cards: u32, shoe: &mut Composition, rules: &rules::TableRules, weight: f64, outcomes: &mut DealerOutcomes)
+{
+    let total = best_total(hard_total, has_ace);
+    if num_cards == 2 && total == 21
+    {
+        outcomes.blackjack += weight;
+        return;
+    }
+    if total > 21
+    {
+        outcomes.bust += weight;
+        return;
+    }
+    if num_cards >= 2 && dealer_stands(hard_total, has_ace, rules.dealer_hits_soft_17)
+    {
+        outcomes.totals[total as usize - 17] += weight;
+        return;
+    }
+    if shoe.is_empty()
+    {
+        // The table reshuffles when it runs dry, so keep drawing from a fresh shoe.
+        return dealer_draw(hard_total, has_ace, num_cards, &mut Composition::with_decks(rules.decks), rules, weight, outcomes);
+    }
+
+    let remaining = shoe.len() as f64;
+    for value in 1..=TEN
+    {
+        let count = shoe.count(value);
+        if count == 0
+        {
+            continue;
+        }
+        let probability = count as f64 / remaining;
+        shoe.remove_value(value);
+        dealer_draw(hard_total + value, has_ace || value == 1, num_cards + 1, shoe, rules, weight * probability, outcomes);
+        shoe.add_value(value);
+    }
+}
+
+pub fn dealer_outcomes(up_card: rank::Rank, shoe: &Composition, rules: &rules::TableRules) -> DealerOutcomes
+{
+    let up_value = point_value(up_card);
+    let mut shoe = *shoe;
+    let mut outcomes = DealerOutcomes::default();
+    match (rules.dealer_peeks, blackjack_hole_card(up_value))
+    {
+        (true, Some(hole_card)) => {
+            let remaining = shoe.len() - shoe.count(hole_card);
+            for value in (1..=TEN).filter(|value| *value != hole_card)
+            {
+                let count = shoe.count(value);
+                if count == 0
+                {
+                    continue;
+                }
+                let probability = count as f64 / remaining as f64;
+                shoe.remove_value(value);
+                dealer_draw(up_value + value, up_value == 1 || value == 1, 2, &mut shoe, rules, probability, &mut outcomes);
+                shoe.add_value(value);
+            }
+        }
+        _ => dealer_draw(up_value, up_value == 1, 1, &mut shoe, rules, 1.0, &mut outcomes),
+    };
+    outcomes
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    const EPSILON: f64 = 1e-9;
+
+    fn six_decks_without(cards: &str) -> Composition
+    {
+        let mut shoe = Composition::with_decks(6);
+        for card in cards.split_whitespace()
+        {
+            assert!(shoe.remove(&card.parse().unwrap()));
+        }
+        shoe
+    }
+
+    #[test]
+    fn test_composition()
+    {
+        let mut shoe = Composition::with_decks(2);
+        assert_eq!(shoe.len(), 104);
+        assert_eq!(shoe.count(1), 8);
+        assert_eq!(shoe.count(TEN), 32);
+        assert_eq!(shoe.probability(TEN), 32.0 / 104.0);
+
+        assert!(shoe.remove(&"KS".parse().unwrap()));
+        assert_eq!(shoe.count(TEN), 31);
+
+        let mut shoe = Composition::from_cards(&[card::Card { rank: rank::Rank::Ace, suit: suit::Suit::Clubs }]);
+        assert!(shoe.remove_value(1));
+        assert!(!shoe.remove_value(1));
+        assert!(shoe.is_empty());
+        assert_eq!(shoe.probability(1), 0.0);
+    }
+
+    #[test]
+    fn test_outcomes_sum_to_one()
+    {
+        for rules in [rules::TableRules::vegas_strip(), rules::TableRules::downtown(), rules::TableRules::european()]
+        {
+            for r in rank::Rank::Ace as u32..=rank::Rank::Ten as u32
+            {
+                let up_card = rank::Rank::try_from(r).unwrap();
+                let shoe = six_decks_without(&format!("{}S", up_card.symbol()));
+                let outcomes = dealer_outcomes(up_card, &shoe, &rules);
+                assert!((outcomes.sum() - 1.0).abs() < EPSILON, "{:?} {}", up_card, outcomes);
+            }
+        }
+    }
+
+    #[test]
+    fn test_tiny_shoe()
+    {
+        let shoe = Composition::from_cards(&"7C 8D".split_whitespace().map(|card| card.parse().unwrap()).collect::<Vec<card::Card>>());
+        let outcomes = dealer_outcomes(rank::Rank::King, &shoe, &rules::TableRules::default());
+
+        assert_eq!(outcomes.total(17), 0.5);
+        assert_eq!(outcomes.total(18), 0.5);
+        assert_eq!(outcomes.total(16), 0.0);
+        assert_eq!(outcomes.bust, 0.0);
+
+        let shoe = Composition::from_cards(&"6C TD".split_whitespace().map(|card| card.parse().unwrap()).collect::<Vec<card::Card>>());
+        let outcomes = dealer_outcomes(rank::Rank::Six, &shoe, &rules::TableRules::default());
+        assert_eq!(outcomes.bust, 1.0);
+
+        let shoe = Composition::from_cards(&["2C".parse().unwrap()]);
+        let outcomes = dealer_outcomes(rank::Rank::King, &shoe, &rules::TableRules::default());
+        assert!((outcomes.sum() - 1.0).abs() < EPSILON, "{}", outcomes);
+        assert!(outcomes.bust > 0.0 && outcomes.total(17) > 0.0);
+    }
+
+    #[test]
+    fn test_peek_removes_blackjack()
+    {
+        let shoe = six_decks_without("AS");
+        let peek = dealer_outcomes(rank::Rank::Ace, &shoe, &rules::TableRules::vegas_strip());
+        let no_peek = dealer_outcomes(rank::Rank::Ace, &shoe, &rules::TableRules::european());
+
+        assert_eq!(peek.blackjack, 0.0);
+        assert!((no_peek.blackjack - 96.0 / 311.0).abs() < EPSILON);
+        for total in 17..=21
+        {
+            assert!((no_peek.total(total) - peek.total(total) * (1.0 - no_peek.blackjack)).abs() < EPSILON);
+        }
+
+        let shoe = six_decks_without("TS");
+        let no_peek = dealer_outcomes(rank::Rank::Ten, &shoe, &rules::TableRules::european());
+        assert!((no_peek.blackjack - 24.0 / 311.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn test_known_probabilities()
+    {
+        let s17 = rules::TableRules::vegas_strip();
+        let h17 = rules::TableRules { dealer_hits_soft_17: true, ..s17 };
+        let shoe = six_decks_without("6S");
+
+        let stands = dealer_outcomes(rank::Rank::Six, &shoe, &s17);
+        let hits = dealer_outcomes(rank::Rank::Six, &shoe, &h17);
+        assert!((stands.bust - 0.4228).abs() < 0.001, "{}", stands);
+        assert!(hits.bust > stands.bust);
+        assert!(hits.total(17) < stands.total(17));
+
+        let shoe = six_decks_without("TS");
+        let ten = dealer_outcomes(rank::Rank::King, &shoe, &s17);
+        assert!((ten.bust - 0.2310).abs() < 0.002, "{}", ten);
+        assert!(ten.total(20) > ten.total(19));
+    }
+
+    #[test]
+    fn test_best_total()
+    {
+        assert_eq!(best_total(7, true), 17);
+        assert_eq!(best_total(12, true), 12);
+        assert_eq!(best_total(11, true), 21);
+        assert_eq!(best_total(16, false), 16);
+        assert_eq!(point_value(rank::Rank::Queen), TEN);
+        assert_eq!(point_value(rank::Rank::Ace), 1);
+    }
+}
@@ -0,0 +1,228 @@
+use std::fmt;
+use std::error;
+
+use crate::rules;
+use crate::table;
+
+pub const DEFAULT_BANKROLL: u32 = 1000;
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum InvalidBet
+{
+    BelowMinimum(u32),
+    AboveMaximum(u32),
+    InsufficientFunds(u32),
+}
+
+impl fmt::Display for InvalidBet
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match &self
+        {
+            InvalidBet::BelowMinimum(minimum) => write!(f, "the table minimum is {} chips", minimum),
+            InvalidBet::AboveMaximum(maximum) => write!(f, "the table maximum is {} chips", maximum),
+            InvalidBet::InsufficientFunds(balance) => write!(f, "you only have {} chips", balance),
+        }
+    }
+}
+
+impl error::Error for InvalidBet {}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Bankroll
+{
+    balance: u32,
+}
+
+impl Default for Bankroll
+{
+    fn default() -> Self
+    {
+        Bankroll::new(DEFAULT_BANKROLL)
+    }
+}
+
+impl Bankroll
+{
+    pub fn new(balance: u32) -> Bankroll
+    {
+        Bankroll { balance }
+    }
+
+    pub fn balance(&self) -> u32
+    {
+        self.balance
+    }
+
+    pub fn can_cover(&self, amount: u32) -> bool
+    {
+        amount <= self.balance
+    }
+
+    pub fn is_broke(&self, rules: &rules::TableRules) -> bool
+    {
+        self.balance < rules.min_bet
+    }
+
+    pub fn check_bet(&self, bet: u32, rules: &rules::TableRules) -> Result<(), InvalidBet>
+    {
+        if bet < rules.min_bet
+        {
+            return Err(InvalidBet::BelowMinimum(rules.min_bet));
+        }
+        if bet > rules.max_bet
+        {
+            return Err(InvalidBet::AboveMaximum(rules.max_bet));
+        }
+        if !self.can_cover(bet)
+        {
+            return Err(InvalidBet::InsufficientFunds(self.balance));
+        }
+        Ok(())
+    }
+
+    pub fn settle(&mut self, bet: u32, result: &table::RoundResult) -> i64
+    {
+        let mut winnings: i64 = result.hands.iter().map(|hand_result| chips(bet, hand_result.net)).sum();
+        if let Some(insurance) = result.insurance
+        {
+            winnings += insurance_chips(bet, result, insurance);
+        }
+        self.balance = (self.balance as i64 + winnings).max(0) as u32;
+        winnings
+    }
+}
+
+// Fractions of a chip are dropped, so half of an odd bet is rounded the same way whether it is won or lost.
+pub fn chips(bet: u32, net: f64) -> i64
+{
+    (bet as f64 * net).trunc() as i64
+}
+
+pub fn insurance_stake(bet: u32) -> u32
+{
+    bet / 2
+}
+
+fn insurance_chips(bet: u32, result: &table::RoundResult, insurance: f64) -> i64
+{
+    let stake = insurance_stake(bet) as i64;
+    match result.hands.first().filter(|hand_result| hand_result.hand.value().is_blackjack())
+    {
+        // Even money pays exactly the bet, whatever the blackjack itself paid.
+        Some(hand_result) => bet as i64 - chips(bet, hand_result.net),
+        None if insurance > 0.0 => 2 * stake,
+        None => -stake,
+    }
+}
+
+impl fmt::Display for Bankroll
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        write!(f, "{} chips", self.balance)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::hand;
+    use crate::outcome;
+
+    fn round_of(nets: &[f64], insurance: Option<f64>) -> table::RoundResult
+    {
+        table::RoundResult {
+            hands: nets.iter().map(|net| table::HandResult { hand: hand::Hand::new(), outcome: outcome::Outcome::Push, net: *net }).collect(),
+            dealer_hand: hand::Hand::new(),
+            insurance,
+        }
+    }
+
+    #[test]
+    fn test_check_bet()
+    {
+        let rules = rules::TableRules { min_bet: 10, max_bet: 500, ..rules::TableRules::default() };
+        let bankroll = Bankroll::new(200);
+
+        assert_eq!(bankroll.check_bet(10, &rules), Ok(()));
+        assert_eq!(bankroll.check_bet(200, &rules), Ok(()));
+        assert_eq!(bankroll.check_bet(5, &rules), Err(InvalidBet::BelowMinimum(10)));
+        assert_eq!(bankroll.check_bet(600, &rules), Err(InvalidBet::AboveMaximum(500)));
+        assert_eq!(bankroll.check_bet(250, &rules), Err(InvalidBet::InsufficientFunds(200)));
+    }
+
+    #[test]
+    fn test_is_broke()
+    {
+        let rules = rules::TableRules { min_bet: 10, ..rules::TableRules::default() };
+
+        assert!(!Bankroll::new(10).is_broke(&rules));
+        assert!(Bankroll::new(9).is_broke(&rules));
+        assert!(Bankroll::new(0).is_broke(&rules));
+        assert_eq!(Bankroll::default().balance(), DEFAULT_BANKROLL);
+    }
+
+    #[test]
+    fn test_settle_payouts()
+    {
+        let mut bankroll = Bankroll::new(100);
+
+        assert_eq!(bankroll.settle(10, &round_of(&[1.5], None)), 15);
+        assert_eq!(bankroll.balance(), 115);
+        assert_eq!(bankroll.settle(10, &round_of(&[1.0], None)), 10);
+        assert_eq!(bankroll.balance(), 125);
+        assert_eq!(bankroll.settle(10, &round_of(&[0.0], None)), 0);
+        assert_eq!(bankroll.balance(), 125);
+        assert_eq!(bankroll.settle(10, &round_of(&[-1.0], None)), -10);
+        assert_eq!(bankroll.balance(), 115);
+        assert_eq!(bankroll.settle(10, &round_of(&[-0.5], None)), -5);
+        assert_eq!(bankroll.balance(), 110);
+        assert_eq!(bankroll.settle(10, &round_of(&[2.0, -1.0], None)), 10);
+        assert_eq!(bankroll.balance(), 120);
+        assert_eq!(bankroll.settle(10, &round_of(&[-1.0], Some(1.0))), 0);
+        assert_eq!(bankroll.balance(), 120);
+        assert_eq!(bankroll.settle(10, &round_of(&[1.0], Some(-0.5))), 5);
+        assert_eq!(bankroll.balance(), 125);
+    }
+
+    #[test]
+    fn test_settle_drops_fractional_chips()
+    {
+        let mut bankroll = Bankroll::new(100);
+
+        assert_eq!(bankroll.settle(5, &round_of(&[1.5], None)), 7);
+        assert_eq!(bankroll.settle(5, &round_of(&[-0.5], None)), -2);
+        assert_eq!(bankroll.balance(), 105);
+    }
+
+    #[test]
+    fn test_settle_insurance_on_an_odd_bet()
+    {
+        let mut bankroll = Bankroll::new(100);
+        assert_eq!(insurance_stake(25), 12);
+        assert_eq!(bankroll.settle(25, &round_of(&[1.0], Some(-0.5))), 13);
+        assert_eq!(bankroll.settle(25, &round_of(&[-1.0], Some(1.0))), -1);
+        assert_eq!(bankroll.settle(25, &round_of(&[-0.5], None)), -12);
+        assert_eq!(bankroll.balance(), 100);
+
+        let mut blackjack = round_of(&[1.5], Some(-0.5));
+        blackjack.hands[0].hand.add_card_to_hand("AS".parse().unwrap());
+        blackjack.hands[0].hand.add_card_to_hand("KD".parse().unwrap());
+        assert_eq!(bankroll.settle(25, &blackjack), 25);
+        blackjack.hands[0].net = 0.0;
+        blackjack.insurance = Some(1.0);
+        assert_eq!(bankroll.settle(25, &blackjack), 25);
+    }
+
+    #[test]
+    fn test_format_bankroll()
+    {
+        assert_eq!(format!("{}", Bankroll::new(250)), "250 chips");
+        assert_eq!(format!("{}", InvalidBet::BelowMinimum(10)), "the table minimum is 10 chips");
+        assert_eq!(format!("{}", InvalidBet::AboveMaximum(500)), "the table maximum is 500 chips");
+        assert_eq!(format!("{}", InvalidBet::InsufficientFunds(20)), "you only have 20 chips");
+    }
+}
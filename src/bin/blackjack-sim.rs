@@ -0,0 +1,138 @@
+use std::env;
+use std::process;
+use std::thread;
+use std::time;
+
+use rand::Rng;
+
+use blackjack::rules;
+use blackjack::sim;
+use blackjack::strategy;
+use blackjack::table;
+
+#[derive(Debug, PartialEq)]
+struct Options
+{
+    rounds: u64,
+    seed: Option<u64>,
+    rules: rules::TableRules,
+    seats: usize,
+    threads: usize,
+}
+
+impl Default for Options
+{
+    fn default() -> Self
+    {
+        Options { rounds: 1_000_000, seed: None, rules: rules::TableRules::default(), seats: 1, threads: thread::available_parallelism().map(|threads| threads.get()).unwrap_or(1) }
+    }
+}
+
+fn parse_options<I: Iterator<Item = String>>(mut args: I) -> Result<Options, String>
+{
+    let mut options = Options::default();
+    let mut rule_options = rules::RuleOptions::default();
+    while let Some(arg) = args.next()
+    {
+        let (name, value) = match arg.split_once('=')
+        {
+            Some((name, value)) => (name, Some(value.to_string())),
+            None => (arg.as_str(), None),
+        };
+        if rule_options.parse_arg(name, value.clone(), &mut args)?
+        {
+            continue;
+        }
+        match name
+        {
+            "--rounds" => {
+                options.rounds = match value.or_else(|| args.next()).map(|value| value.replace('_', "").parse::<u64>())
+                {
+                    Some(Ok(rounds)) if rounds > 0 => rounds,
+                    _ => return Err(String::from("--rounds expects a positive integer")),
+                };
+            }
+            "--seed" => {
+                options.seed = match value.or_else(|| args.next()).map(|value| value.parse::<u64>())
+                {
+                    Some(Ok(seed)) => Some(seed),
+                    _ => return Err(String::from("--seed expects a non-negative integer")),
+                };
+            }
+            "--seats" => {
+                options.seats = match value.or_else(|| args.next()).map(|value| value.parse::<usize>())
+                {
+                    Some(Ok(seats)) if (1..=table::MAX_SEATS).contains(&seats) => seats,
+                    _ => return Err(format!("--seats expects a number from 1 to {}", table::MAX_SEATS)),
+                };
+            }
+            "--threads" => {
+                options.threads = match value.or_else(|| args.next()).map(|value| value.parse::<usize>())
+                {
+                    Some(Ok(threads)) if threads > 0 => threads,
+                    _ => return Err(String::from("--threads expects a positive integer")),
+                };
+            }
+            _ => return Err(format!("Unknown argument: {}", arg)),
+        }
+    }
+    options.rules = rule_options.rules();
+    Ok(options)
+}
+
+fn main()
+{
+    let options = match parse_options(env::args().skip(1))
+    {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("{}", error);
+            eprintln!("Usage: blackjack-sim [--rounds <n>] [--seed <n>] [--rules <preset>] [--decks <1-8>] [--h17|--s17] [--seats <1-{}>] [--threads <n>]", table::MAX_SEATS);
+            process::exit(2);
+        }
+    };
+    let seed = options.seed.unwrap_or_else(|| rand::thread_rng().gen());
+    println!("Seed: {} (reproduce with --seed {} --threads {})", seed, seed, options.threads);
+    println!("Table rules: {}", options.rules);
+    println!("Simulating {} rounds with {} basic strategy seat(s) on {} thread(s)", options.rounds, options.seats, options.threads);
+    let started = time::Instant::now();
+    match sim::simulate_parallel::<strategy::BasicStrategy>(options.rules, options.seats, options.rounds, seed, options.threads)
+    {
+        Ok(statistics) => {
+            println!("{}", statistics);
+            println!("Finished in {:.2}s", started.elapsed().as_secs_f64());
+        }
+        Err(error) => {
+            eprintln!("The simulation could not be finished: {}", error);
+            process::exit(1);
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn test_parse_options()
+    {
+        let args = |values: &[&str]| values.iter().map(|value| value.to_string()).collect::<Vec<String>>().into_iter();
+
+        assert_eq!(parse_options(args(&[])), Ok(Options::default()));
+        assert_eq!(parse_options(args(&["--rounds", "1_000"])).unwrap().rounds, 1000);
+        assert_eq!(parse_options(args(&["--seed=9"])).unwrap().seed, Some(9));
+        assert_eq!(parse_options(args(&["--rules", "downtown"])).unwrap().rules, rules::TableRules::downtown());
+        assert_eq!(parse_options(args(&["--decks", "2", "--rules", "ac"])).unwrap().rules.decks, 2);
+        assert!(parse_options(args(&["--h17"])).unwrap().rules.dealer_hits_soft_17);
+        assert!(!parse_options(args(&["--rules", "downtown", "--s17"])).unwrap().rules.dealer_hits_soft_17);
+        assert_eq!(parse_options(args(&["--seats", "7"])).unwrap().seats, 7);
+        assert_eq!(parse_options(args(&["--threads=3"])).unwrap().threads, 3);
+        assert!(parse_options(args(&["--threads", "0"])).is_err());
+        assert!(parse_options(args(&["--rounds", "0"])).is_err());
+        assert!(parse_options(args(&["--rules", "macau"])).is_err());
+        assert!(parse_options(args(&["--decks", "9"])).is_err());
+        assert!(parse_options(args(&["--seats", "8"])).is_err());
+        assert!(parse_options(args(&["--coach"])).is_err());
+    }
+}
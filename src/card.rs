@@ -1,9 +1,24 @@
 use std::fmt;
+use std::str;
+use std::error;
 
 use crate::suit;
 use crate::rank;
 
 #[derive(Debug, Eq, PartialEq)]
+pub struct InvalidCardNotation;
+
+impl fmt::Display for InvalidCardNotation
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        write!(f, "cards must be written as a rank and a suit, e.g. \"AS\", \"10h\" or \"Q♣\"")
+    }
+}
+
+impl error::Error for InvalidCardNotation {}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub struct Card
 {
     pub rank: rank::Rank,
@@ -14,7 +29,28 @@ impl fmt::Display for Card
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
     {
-        write!(f, "{} of {}", self.rank, self.suit)
+        match f.alternate()
+        {
+            true => write!(f, "{:#}{:#}", self.rank, self.suit),
+            false => write!(f, "{} of {}", self.rank, self.suit),
+        }
+    }
+}
+
+impl str::FromStr for Card
+{
+    type Err = InvalidCardNotation;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err>
+    {
+        let s = s.trim();
+        let (split_at, _) = s.char_indices().last().ok_or(InvalidCardNotation)?;
+        let (rank, suit) = s.split_at(split_at);
+
+        Ok(Card {
+            rank: rank.parse().map_err(|_| InvalidCardNotation)?,
+            suit: suit.parse().map_err(|_| InvalidCardNotation)?,
+        })
     }
 }
 
@@ -81,4 +117,45 @@ mod tests
         assert_eq!(queen_hearts.get_point_value(), 10);
         assert_eq!(king_hearts.get_point_value(), 10);
     }
+
+    #[test]
+    fn test_format_card_compact()
+    {
+        let ace_spades: Card = Card { rank: rank::Rank::Ace, suit: suit::Suit::Spades};
+        let ten_diamonds: Card = Card { rank: rank::Rank::Ten, suit: suit::Suit::Diamonds};
+        let queen_clubs: Card = Card { rank: rank::Rank::Queen, suit: suit::Suit::Clubs};
+        assert_eq!(format!("{:#}", ace_spades), "A♠");
+        assert_eq!(format!("{:#}", ten_diamonds), "T♦");
+        assert_eq!(format!("{:#}", queen_clubs), "Q♣");
+    }
+
+    #[test]
+    fn test_card_from_str()
+    {
+        assert_eq!("AS".parse::<Card>(), Ok(Card { rank: rank::Rank::Ace, suit: suit::Suit::Spades}));
+        assert_eq!("10h".parse::<Card>(), Ok(Card { rank: rank::Rank::Ten, suit: suit::Suit::Hearts}));
+        assert_eq!("Td".parse::<Card>(), Ok(Card { rank: rank::Rank::Ten, suit: suit::Suit::Diamonds}));
+        assert_eq!("Q♣".parse::<Card>(), Ok(Card { rank: rank::Rank::Queen, suit: suit::Suit::Clubs}));
+        assert_eq!(" 7c ".parse::<Card>(), Ok(Card { rank: rank::Rank::Seven, suit: suit::Suit::Clubs}));
+        assert_eq!("".parse::<Card>(), Err(InvalidCardNotation));
+        assert_eq!("A".parse::<Card>(), Err(InvalidCardNotation));
+        assert_eq!("1S".parse::<Card>(), Err(InvalidCardNotation));
+        assert_eq!("AX".parse::<Card>(), Err(InvalidCardNotation));
+        assert_eq!("Ace of Spades".parse::<Card>(), Err(InvalidCardNotation));
+    }
+
+    #[test]
+    fn test_card_round_trip()
+    {
+        let cards = [
+            Card { rank: rank::Rank::Ace, suit: suit::Suit::Spades},
+            Card { rank: rank::Rank::Ten, suit: suit::Suit::Hearts},
+            Card { rank: rank::Rank::Two, suit: suit::Suit::Diamonds},
+            Card { rank: rank::Rank::King, suit: suit::Suit::Clubs},
+        ];
+        for card in cards
+        {
+            assert_eq!(format!("{:#}", card).parse::<Card>(), Ok(card));
+        }
+    }
 }
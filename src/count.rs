@@ -0,0 +1,532 @@
+use std::fmt;
+use std::error;
+
+use crate::card;
+use crate::deck;
+use crate::rank;
+use crate::suit;
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct InvalidTagNotation;
+
+impl fmt::Display for InvalidTagNotation
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        write!(f, "tags must look like \"A=-1 2=1 ... T=-1\"")
+    }
+}
+
+impl error::Error for InvalidTagNotation {}
+
+pub trait CountingSystem: fmt::Debug + Send
+{
+    fn name(&self) -> &str;
+    fn tag(&self, card: &card::Card) -> f64;
+
+    fn side_counts_aces(&self) -> bool
+    {
+        false
+    }
+
+    fn pivot(&self) -> f64
+    {
+        0.0
+    }
+
+    fn deck_total(&self) -> f64
+    {
+        let mut deck = deck::Deck::new();
+        let mut total = 0.0;
+        while let Ok(card) = deck.deal_card()
+        {
+            total += self.tag(&card);
+        }
+        total
+    }
+
+    fn is_balanced(&self) -> bool
+    {
+        self.deck_total() == 0.0
+    }
+
+    fn initial_running_count(&self, num_decks: u32) -> f64
+    {
+        match self.is_balanced()
+        {
+            true => 0.0,
+            false => self.pivot() - self.deck_total() * num_decks as f64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagTable
+{
+    name: String,
+    tags: [f64; 13],
+    side_counts_aces: bool,
+    pivot: f64,
+}
+
+impl TagTable
+{
+    pub fn new(name: &str, tags: &[(rank::Rank, f64)]) -> TagTable
+    {
+        let mut table = TagTable { name: name.to_string(), tags: [0.0; 13], side_counts_aces: false, pivot: 0.0 };
+        for (rank, tag) in tags
+        {
+            table.tags[*rank as usize - 1] = *tag;
+        }
+        table
+    }
+
+    // A ten tag also covers the face cards unless they are given their own tag later on.
+    pub fn from_notation(name: &str, notation: &str) -> Result<TagTable, InvalidTagNotation>
+    {
+        let mut tags = vec![];
+        for entry in notation.split_whitespace()
+        {
+            let (rank, tag) = entry.split_once('=').ok_or(InvalidTagNotation)?;
+            let rank: rank::Rank = rank.parse().map_err(|_| InvalidTagNotation)?;
+            let tag: f64 = tag.parse().map_err(|_| InvalidTagNotation)?;
+            if !tag.is_finite()
+            {
+                return Err(InvalidTagNotation);
+            }
+            match rank
+            {
+                rank::Rank::Ten => tags.extend([rank::Rank::Ten, rank::Rank::Jack, rank::Rank::Queen, rank::Rank::King].map(|rank| (rank, tag))),
+                _ => tags.push((rank, tag)),
+            }
+        }
+        match tags.is_empty()
+        {
+            true => Err(InvalidTagNotation),
+            false => Ok(TagTable::new(name, &tags)),
+        }
+    }
+
+    pub fn with_ace_side_count(mut self) -> TagTable
+    {
+        self.side_counts_aces = true;
+        self
+    }
+
+    pub fn with_pivot(mut self, pivot: f64) -> TagTable
+    {
+        self.pivot = pivot;
+        self
+    }
+
+    pub fn rank_tag(&self, rank: rank::Rank) -> f64
+    {
+        self.tags[rank as usize - 1]
+    }
+
+    fn preset(name: &str, notation: &str) -> TagTable
+    {
+        TagTable::from_notation(name, notation).unwrap()
+    }
+
+    pub fn hi_lo() -> TagTable
+    {
+        TagTable::preset("Hi-Lo", "2=1 3=1 4=1 5=1 6=1 7=0 8=0 9=0 T=-1 A=-1")
+    }
+
+    pub fn ko() -> TagTable
+    {
+        TagTable::preset("KO", "2=1 3=1 4=1 5=1 6=1 7=1 8=0 9=0 T=-1 A=-1").with_pivot(4.0)
+    }
+
+    pub fn hi_opt_i() -> TagTable
+    {
+        TagTable::preset("Hi-Opt I", "2=0 3=1 4=1 5=1 6=1 7=0 8=0 9=0 T=-1 A=0").with_ace_side_count()
+    }
+
+    pub fn hi_opt_ii() -> TagTable
+    {
+        TagTable::preset("Hi-Opt II", "2=1 3=1 4=2 5=2 6=1 7=1 8=0 9=0 T=-2 A=0").with_ace_side_count()
+    }
+
+    pub fn omega_ii() -> TagTable
+    {
+        TagTable::preset("Omega II", "2=1 3=1 4=2 5=2 6=2 7=1 8=0 9=-1 T=-2 A=0").with_ace_side_count()
+    }
+
+    pub fn zen() -> TagTable
+    {
+        TagTable::preset("Zen", "2=1 3=1 4=2 5=2 6=2 7=1 8=0 9=0 T=-2 A=-1")
+    }
+
+    pub fn wong_halves() -> TagTable
+    {
+        TagTable::preset("Wong Halves", "2=0.5 3=1 4=1 5=1.5 6=1 7=0.5 8=0 9=-0.5 T=-1 A=-1")
+    }
+}
+
+impl CountingSystem for TagTable
+{
+    fn name(&self) -> &str
+    {
+        &self.name
+    }
+
+    fn tag(&self, card: &card::Card) -> f64
+    {
+        self.rank_tag(card.rank)
+    }
+
+    fn side_counts_aces(&self) -> bool
+    {
+        self.side_counts_aces
+    }
+
+    fn pivot(&self) -> f64
+    {
+        self.pivot
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RedSeven
+{
+    tags: TagTable,
+}
+
+impl Default for RedSeven
+{
+    fn default() -> Self
+    {
+        RedSeven::new()
+    }
+}
+
+impl RedSeven
+{
+    pub fn new() -> RedSeven
+    {
+        RedSeven { tags: TagTable::preset("Red Seven", "2=1 3=1 4=1 5=1 6=1 7=0 8=0 9=0 T=-1 A=-1") }
+    }
+}
+
+impl CountingSystem for RedSeven
+{
+    fn name(&self) -> &str
+    {
+        self.tags.name()
+    }
+
+    fn tag(&self, card: &card::Card) -> f64
+    {
+        match (card.rank, card.suit)
+        {
+            (rank::Rank::Seven, suit::Suit::Diamonds | suit::Suit::Hearts) => 1.0,
+            _ => self.tags.tag(card),
+        }
+    }
+}
+
+pub fn system_by_name(name: &str) -> Option<Box<dyn CountingSystem>>
+{
+    let system: Box<dyn CountingSystem> = match name.to_lowercase().replace([' ', '_'], "-").as_str()
+    {
+        "hi-lo" | "hilo" => Box::new(TagTable::hi_lo()),
+        "ko" => Box::new(TagTable::ko()),
+        "hi-opt-i" | "hi-opt-1" => Box::new(TagTable::hi_opt_i()),
+        "hi-opt-ii" | "hi-opt-2" => Box::new(TagTable::hi_opt_ii()),
+        "omega-ii" | "omega-2" => Box::new(TagTable::omega_ii()),
+        "zen" => Box::new(TagTable::zen()),
+        "wong-halves" | "halves" => Box::new(TagTable::wong_halves()),
+        "red-seven" | "red-7" => Box::new(RedSeven::new()),
+        _ => return None,
+    };
+    Some(system)
+}
+
+#[derive(Debug)]
+pub struct Counter
+{
+    system: Box<dyn CountingSystem>,
+    num_decks: u32,
+    deck_total: f64,
+    initial_running_count: f64,
+    running_count: f64,
+    cards_seen: u32,
+    aces_seen: u32,
+}
+
+impl Default for Counter
+{
+    fn default() -> Self
+    {
+        Counter::new()
+    }
+}
+
+impl Counter
+{
+    pub fn new() -> Counter
+    {
+        Counter::with_system(Box::new(TagTable::hi_lo()), 1)
+    }
+
+    pub fn with_system(system: Box<dyn CountingSystem>, num_decks: u32) -> Counter
+    {
+        // Both only depend on the system and the shoe size, and true_count needs them on every decision.
+        let deck_total = system.deck_total();
+        let initial_running_count = system.initial_running_count(num_decks);
+        Counter { system, num_decks, deck_total, initial_running_count, running_count: initial_running_count, cards_seen: 0, aces_seen: 0 }
+    }
+
+    pub fn system(&self) -> &dyn CountingSystem
+    {
+        self.system.as_ref()
+    }
+
+    pub fn observe(&mut self, card: &card::Card)
+    {
+        self.running_count += self.system.tag(card);
+        self.cards_seen += 1;
+        if card.rank == rank::Rank::Ace
+        {
+            self.aces_seen += 1;
+        }
+    }
+
+    pub fn reset(&mut self)
+    {
+        self.running_count = self.initial_running_count;
+        self.cards_seen = 0;
+        self.aces_seen = 0;
+    }
+
+    pub fn running_count(&self) -> f64
+    {
+        self.running_count
+    }
+
+    pub fn cards_seen(&self) -> u32
+    {
+        self.cards_seen
+    }
+
+    pub fn true_count(&self, decks_remaining: f64) -> f64
+    {
+        let decks_dealt = self.num_decks as f64 - decks_remaining;
+        let excess = self.running_count - self.initial_running_count - self.deck_total * decks_dealt;
+        match decks_remaining > 0.0
+        {
+            true => excess / decks_remaining,
+            false => excess,
+        }
+    }
+
+    pub fn aces_seen(&self) -> Option<u32>
+    {
+        match self.system.side_counts_aces()
+        {
+            true => Some(self.aces_seen),
+            false => None,
+        }
+    }
+
+    pub fn extra_aces(&self, decks_remaining: f64) -> Option<f64>
+    {
+        let aces_expected = 4.0 * (self.num_decks as f64 - decks_remaining);
+        self.aces_seen().map(|aces_seen| aces_expected - aces_seen as f64)
+    }
+}
+
+impl fmt::Display for Counter
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        write!(f, "{} running count {:+}", self.system.name(), self.running_count)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn count_of(system: Box<dyn CountingSystem>, notation: &str) -> Counter
+    {
+        let mut counter = Counter::with_system(system, 1);
+        counter.running_count = 0.0;
+        let mut deck = deck::Deck::from_notation(notation).unwrap();
+        while let Ok(card) = deck.deal_card()
+        {
+            counter.observe(&card);
+        }
+        counter
+    }
+
+    #[test]
+    fn test_hi_lo_tags()
+    {
+        let hi_lo = TagTable::hi_lo();
+        assert_eq!(hi_lo.rank_tag(rank::Rank::Two), 1.0);
+        assert_eq!(hi_lo.rank_tag(rank::Rank::Six), 1.0);
+        assert_eq!(hi_lo.rank_tag(rank::Rank::Seven), 0.0);
+        assert_eq!(hi_lo.rank_tag(rank::Rank::Nine), 0.0);
+        assert_eq!(hi_lo.rank_tag(rank::Rank::Ten), -1.0);
+        assert_eq!(hi_lo.rank_tag(rank::Rank::King), -1.0);
+        assert_eq!(hi_lo.rank_tag(rank::Rank::Ace), -1.0);
+    }
+
+    #[test]
+    fn test_observe()
+    {
+        let mut counter = count_of(Box::new(TagTable::hi_lo()), "2S 5H KD 8C AS 3D 4C");
+
+        assert_eq!(counter.running_count(), 2.0);
+        assert_eq!(counter.cards_seen(), 7);
+        assert_eq!(counter.aces_seen(), None);
+        assert_eq!(format!("{}", counter), "Hi-Lo running count +2");
+
+        counter.reset();
+        assert_eq!(counter.running_count(), 0.0);
+        assert_eq!(counter.cards_seen(), 0);
+    }
+
+    #[test]
+    fn test_full_deck_is_balanced()
+    {
+        let mut counter = Counter::with_system(Box::new(TagTable::hi_lo()), 6);
+        let mut deck = deck::Deck::with_decks(6);
+        while let Ok(card) = deck.deal_card()
+        {
+            counter.observe(&card);
+        }
+
+        assert_eq!(counter.running_count(), 0.0);
+        assert_eq!(counter.cards_seen(), 312);
+    }
+
+    #[test]
+    fn test_true_count()
+    {
+        let mut counter = Counter::with_system(Box::new(TagTable::hi_lo()), 6);
+        for card in ["2S", "3S", "4S", "5S", "6S", "2H"]
+        {
+            counter.observe(&card.parse().unwrap());
+        }
+
+        assert_eq!(counter.true_count(3.0), 2.0);
+        assert_eq!(counter.true_count(0.5), 12.0);
+        assert_eq!(counter.true_count(0.0), 6.0);
+        assert_eq!(format!("{}", Counter::default()), "Hi-Lo running count +0");
+    }
+
+    #[test]
+    fn test_balanced_systems()
+    {
+        for system in [TagTable::hi_lo(), TagTable::hi_opt_i(), TagTable::hi_opt_ii(), TagTable::omega_ii(), TagTable::zen(), TagTable::wong_halves()]
+        {
+            assert!(system.is_balanced(), "{} should be balanced", system.name());
+            assert_eq!(system.initial_running_count(6), 0.0);
+        }
+        assert!(!TagTable::ko().is_balanced());
+        assert!(!RedSeven::new().is_balanced());
+    }
+
+    #[test]
+    fn test_unbalanced_initial_running_count()
+    {
+        assert_eq!(TagTable::ko().deck_total(), 4.0);
+        assert_eq!(TagTable::ko().initial_running_count(1), 0.0);
+        assert_eq!(TagTable::ko().initial_running_count(6), -20.0);
+        assert_eq!(RedSeven::new().deck_total(), 2.0);
+        assert_eq!(RedSeven::new().initial_running_count(6), -12.0);
+
+        let mut counter = Counter::with_system(Box::new(TagTable::ko()), 2);
+        assert_eq!(counter.running_count(), -4.0);
+        let mut deck = deck::Deck::with_decks(2);
+        while let Ok(card) = deck.deal_card()
+        {
+            counter.observe(&card);
+        }
+        assert_eq!(counter.running_count(), 4.0);
+        assert_eq!(counter.true_count(0.0), 0.0);
+
+        counter.reset();
+        assert_eq!(counter.running_count(), -4.0);
+    }
+
+    #[test]
+    fn test_unbalanced_true_count()
+    {
+        let mut counter = Counter::with_system(Box::new(TagTable::ko()), 2);
+        for card in ["2S", "3S", "4S", "5S", "6S", "7H"]
+        {
+            counter.observe(&card.parse().unwrap());
+        }
+
+        assert_eq!(counter.running_count(), 2.0);
+        assert_eq!(counter.true_count(1.5), (6.0 - 2.0) / 1.5);
+    }
+
+    #[test]
+    fn test_system_tags()
+    {
+        assert_eq!(count_of(Box::new(TagTable::ko()), "2C 7D 8H AS").running_count(), 1.0);
+        assert_eq!(count_of(Box::new(TagTable::hi_opt_i()), "2C 3D 6H TS AS").running_count(), 1.0);
+        assert_eq!(count_of(Box::new(TagTable::hi_opt_ii()), "4C 5D 7H QS AS").running_count(), 3.0);
+        assert_eq!(count_of(Box::new(TagTable::omega_ii()), "6C 7D 8H 9S JS").running_count(), 0.0);
+        assert_eq!(count_of(Box::new(TagTable::zen()), "4C 7D AH KS").running_count(), 0.0);
+        assert_eq!(count_of(Box::new(TagTable::wong_halves()), "2C 5D 7H 9S").running_count(), 2.0);
+        assert_eq!(count_of(Box::new(RedSeven::new()), "7D 7H 7C 7S").running_count(), 2.0);
+    }
+
+    #[test]
+    fn test_ace_side_count()
+    {
+        let mut counter = Counter::with_system(Box::new(TagTable::hi_opt_i()), 1);
+        for card in ["AS", "5D", "AH", "TC"]
+        {
+            counter.observe(&card.parse().unwrap());
+        }
+
+        assert_eq!(counter.running_count(), 0.0);
+        assert_eq!(counter.aces_seen(), Some(2));
+        assert_eq!(counter.extra_aces(0.5), Some(0.0));
+        assert_eq!(counter.extra_aces(0.75), Some(-1.0));
+        assert_eq!(Counter::new().extra_aces(0.5), None);
+    }
+
+    #[test]
+    fn test_custom_tag_table()
+    {
+        let custom = TagTable::from_notation("Custom", "2=1 3=1 4=1 5=1 6=1 T=-1 K=-2 A=-1").unwrap();
+
+        assert_eq!(custom.name(), "Custom");
+        assert_eq!(custom.rank_tag(rank::Rank::Seven), 0.0);
+        assert_eq!(custom.rank_tag(rank::Rank::Jack), -1.0);
+        assert_eq!(custom.rank_tag(rank::Rank::King), -2.0);
+        assert!(!custom.is_balanced());
+        assert_eq!(custom.deck_total(), -4.0);
+        assert_eq!(custom.initial_running_count(2), 8.0);
+    }
+
+    #[test]
+    fn test_invalid_tag_notation()
+    {
+        assert_eq!(TagTable::from_notation("Bad", ""), Err(InvalidTagNotation));
+        assert_eq!(TagTable::from_notation("Bad", "2:1"), Err(InvalidTagNotation));
+        assert_eq!(TagTable::from_notation("Bad", "X=1"), Err(InvalidTagNotation));
+        assert_eq!(TagTable::from_notation("Bad", "2=one"), Err(InvalidTagNotation));
+        assert_eq!(TagTable::from_notation("Bad", "2=NaN"), Err(InvalidTagNotation));
+    }
+
+    #[test]
+    fn test_system_by_name()
+    {
+        assert_eq!(system_by_name("KO").unwrap().name(), "KO");
+        assert_eq!(system_by_name("hi-opt ii").unwrap().name(), "Hi-Opt II");
+        assert_eq!(system_by_name("Wong_Halves").unwrap().name(), "Wong Halves");
+        assert_eq!(system_by_name("red-seven").unwrap().name(), "Red Seven");
+        assert!(system_by_name("uston").is_none());
+    }
+}
@@ -1,4 +1,5 @@
 use std::fmt;
+use std::error;
 use std::collections::VecDeque;
 use rand::prelude::*;
 
@@ -6,11 +7,42 @@ use crate::card;
 use crate::suit;
 use crate::rank;
 
+#[derive(Debug, Eq, PartialEq)]
+pub struct EmptyDeck;
+
+impl fmt::Display for EmptyDeck
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        write!(f, "no cards left to deal")
+    }
+}
+
+impl error::Error for EmptyDeck {}
+
 #[derive(Debug)]
 pub struct Deck(VecDeque<card::Card>);
 
+impl Default for Deck
+{
+    fn default() -> Self
+    {
+        Deck::new()
+    }
+}
+
 impl Deck
 {
+    pub fn with_decks(num_decks: u32) -> Deck
+    {
+        let mut new_deck: Deck = Deck(VecDeque::new());
+        for _ in 0..num_decks
+        {
+            new_deck.0.append(&mut Deck::new().0);
+        }
+        new_deck
+    }
+
     pub fn new() -> Deck
     {
         let mut new_deck: Deck = Deck(VecDeque::new());
@@ -21,23 +53,46 @@ impl Deck
                 let new_suit = suit::Suit::try_from(s);
                 let new_rank = rank::Rank::try_from(r);
 
-                match (new_rank, new_suit)
+                if let (Ok(rr), Ok(ss)) = (new_rank, new_suit)
                 {
-                    (Ok(rr), Ok(ss)) => new_deck.0.push_back(card::Card { rank: rr , suit: ss } ),
-                    _ => ()
-                };
+                    new_deck.0.push_back(card::Card { rank: rr , suit: ss } );
+                }
             }
         }
 
         new_deck
     }
 
+    pub fn from_cards(cards: Vec<card::Card>) -> Deck
+    {
+        Deck(VecDeque::from(cards))
+    }
+
+    pub fn from_notation(notation: &str) -> Result<Deck, card::InvalidCardNotation>
+    {
+        notation.split_whitespace().map(str::parse).collect::<Result<Vec<card::Card>, card::InvalidCardNotation>>().map(Deck::from_cards)
+    }
+
+    pub fn len(&self) -> usize
+    {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool
+    {
+        self.0.is_empty()
+    }
+
     pub fn shuffle(&mut self)
     {
-        let mut rng = rand::thread_rng();
+        self.shuffle_with_rng(&mut rand::thread_rng());
+    }
+
+    pub fn shuffle_with_rng<R: Rng + ?Sized>(&mut self, rng: &mut R)
+    {
         for _ in 1..5
         {
-            self.0.make_contiguous().shuffle(&mut rng);
+            self.0.make_contiguous().shuffle(rng);
         }
     }
 
@@ -47,9 +102,9 @@ impl Deck
         self.0.append(&mut collected_cards);
     }
 
-    pub fn deal_card(&mut self) -> card::Card
+    pub fn deal_card(&mut self) -> Result<card::Card, EmptyDeck>
     {
-        self.0.pop_front().unwrap()
+        self.0.pop_front().ok_or(EmptyDeck)
     }
 }
 
@@ -61,7 +116,11 @@ impl fmt::Display for Deck
         for (index, card) in self.0.iter().enumerate()
         {
             if index != 0 { write!(f, ", ")?; }
-            write!(f, "{}", card)?;
+            match f.alternate()
+            {
+                true => write!(f, "{:#}", card)?,
+                false => write!(f, "{}", card)?,
+            }
         }
         write!(f, " ]")
     }
@@ -94,6 +153,15 @@ mod tests
         assert_eq!(new_deck.0.len(), 52);
     }
 
+    #[test]
+    fn test_with_decks_card_count()
+    {
+        assert_eq!(Deck::with_decks(1).0.len(), 52);
+        assert_eq!(Deck::with_decks(2).0.len(), 104);
+        assert_eq!(Deck::with_decks(6).0.len(), 312);
+        assert_eq!(Deck::with_decks(8).0.len(), 416);
+    }
+
     #[test]
     fn test_shuffled_deck()
     {
@@ -114,6 +182,22 @@ mod tests
         assert_eq!(new_deck.0.len(), 52);
     }
 
+    #[test]
+    fn test_shuffle_with_seeded_rng()
+    {
+        let mut first_deck = Deck::new();
+        let mut second_deck = Deck::new();
+        let mut third_deck = Deck::new();
+
+        first_deck.shuffle_with_rng(&mut rand::rngs::StdRng::seed_from_u64(42));
+        second_deck.shuffle_with_rng(&mut rand::rngs::StdRng::seed_from_u64(42));
+        third_deck.shuffle_with_rng(&mut rand::rngs::StdRng::seed_from_u64(43));
+
+        assert_eq!(first_deck.0, second_deck.0);
+        assert_ne!(first_deck.0, third_deck.0);
+        assert_ne!(first_deck.0, Deck::new().0);
+    }
+
     #[test]
     fn test_collect_played_cards()
     {
@@ -134,6 +218,21 @@ mod tests
         assert_eq!(new_deck.0.len(), 52);
     }
 
+    #[test]
+    fn test_len()
+    {
+        let mut new_deck = Deck::new();
+        assert_eq!(new_deck.len(), 52);
+        assert!(!new_deck.is_empty());
+
+        for _ in 0..52
+        {
+            assert!(new_deck.deal_card().is_ok());
+        }
+        assert_eq!(new_deck.len(), 0);
+        assert!(new_deck.is_empty());
+    }
+
     #[test]
     fn test_deal_card()
     {
@@ -144,6 +243,61 @@ mod tests
         let dealt_card = new_deck.deal_card();
 
         assert_eq!(new_deck.0.len(), 51);
-        assert_eq!(dealt_card, card::Card { rank: rank::Rank::Ace, suit: suit::Suit::Diamonds });
+        assert_eq!(dealt_card, Ok(card::Card { rank: rank::Rank::Ace, suit: suit::Suit::Diamonds }));
+    }
+
+    #[test]
+    fn test_deal_card_empty_deck()
+    {
+        let mut new_deck = Deck::new();
+
+        for _ in 0..52
+        {
+            assert!(new_deck.deal_card().is_ok());
+        }
+
+        assert_eq!(new_deck.deal_card(), Err(EmptyDeck));
+        assert_eq!(format!("{}", EmptyDeck), "no cards left to deal");
+
+        new_deck.collect_played_cards(vec![card::Card { rank: rank::Rank::Two, suit: suit::Suit::Clubs }]);
+        assert_eq!(new_deck.deal_card(), Ok(card::Card { rank: rank::Rank::Two, suit: suit::Suit::Clubs }));
+    }
+
+    #[test]
+    fn test_from_cards()
+    {
+        let mut deck = Deck::from_cards(vec![card::Card { rank: rank::Rank::Ace, suit: suit::Suit::Spades}, card::Card { rank: rank::Rank::King, suit: suit::Suit::Diamonds}]);
+
+        assert_eq!(deck.len(), 2);
+        assert_eq!(deck.deal_card(), Ok(card::Card { rank: rank::Rank::Ace, suit: suit::Suit::Spades}));
+        assert_eq!(deck.deal_card(), Ok(card::Card { rank: rank::Rank::King, suit: suit::Suit::Diamonds}));
+        assert_eq!(deck.deal_card(), Err(EmptyDeck));
+    }
+
+    #[test]
+    fn test_from_notation()
+    {
+        let deck = Deck::from_notation("AS KD 8H 7C 10h Td 2c").unwrap();
+
+        assert_eq!(Vec::from(deck.0), vec![
+            card::Card { rank: rank::Rank::Ace, suit: suit::Suit::Spades},
+            card::Card { rank: rank::Rank::King, suit: suit::Suit::Diamonds},
+            card::Card { rank: rank::Rank::Eight, suit: suit::Suit::Hearts},
+            card::Card { rank: rank::Rank::Seven, suit: suit::Suit::Clubs},
+            card::Card { rank: rank::Rank::Ten, suit: suit::Suit::Hearts},
+            card::Card { rank: rank::Rank::Ten, suit: suit::Suit::Diamonds},
+            card::Card { rank: rank::Rank::Two, suit: suit::Suit::Clubs},
+        ]);
+        assert!(Deck::from_notation("").unwrap().is_empty());
+        assert_eq!(format!("{:#}", Deck::from_notation("Q♣ a♥ 9s").unwrap()), "[ Q♣, A♥, 9♠ ]");
+    }
+
+    #[test]
+    fn test_from_notation_invalid()
+    {
+        assert_eq!(Deck::from_notation("AS KX").unwrap_err(), card::InvalidCardNotation);
+        assert_eq!(Deck::from_notation("1S").unwrap_err(), card::InvalidCardNotation);
+        assert_eq!(Deck::from_notation("A").unwrap_err(), card::InvalidCardNotation);
+        assert_eq!(Deck::from_notation("AS,KD").unwrap_err(), card::InvalidCardNotation);
     }
 }
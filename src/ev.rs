@@ -0,0 +1,419 @@
+use std::fmt;
+use std::collections::HashMap;
+
+use crate::analysis;
+use crate::hand;
+use crate::rank;
+use crate::rules;
+use crate::player::BlackjackAction;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ActionEvs
+{
+    pub stand: f64,
+    pub hit: Option<f64>,
+    pub double: Option<f64>,
+    pub split: Option<f64>,
+    pub surrender: Option<f64>,
+}
+
+impl ActionEvs
+{
+    pub fn ev(&self, action: BlackjackAction) -> Option<f64>
+    {
+        match action
+        {
+            BlackjackAction::Stay => Some(self.stand),
+            BlackjackAction::Hit => self.hit,
+            BlackjackAction::Double => self.double,
+            BlackjackAction::Split => self.split,
+            BlackjackAction::Surrender => self.surrender,
+        }
+    }
+
+    pub fn best(&self) -> (BlackjackAction, f64)
+    {
+        let mut best = (BlackjackAction::Stay, self.stand);
+        for action in [BlackjackAction::Hit, BlackjackAction::Double, BlackjackAction::Split, BlackjackAction::Surrender]
+        {
+            if let Some(ev) = self.ev(action).filter(|ev| *ev > best.1)
+            {
+                best = (action, ev);
+            }
+        }
+        best
+    }
+}
+
+impl fmt::Display for ActionEvs
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        let (best, _) = self.best();
+        for action in [BlackjackAction::Stay, BlackjackAction::Hit, BlackjackAction::Double, BlackjackAction::Split, BlackjackAction::Surrender]
+        {
+            if let Some(ev) = self.ev(action)
+            {
+                let marker = if action == best { "  <- best" } else { "" };
+                writeln!(f, "{:<10} {:+.4}%{}", format!("{}:", action), ev * 100.0, marker)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+struct PlayerHand
+{
+    hard_total: u32,
+    has_ace: bool,
+    num_cards: u32,
+    split: bool,
+}
+
+impl PlayerHand
+{
+    fn total(&self) -> u32
+    {
+        analysis::best_total(self.hard_total, self.has_ace)
+    }
+
+    fn draw(&self, value: u32) -> PlayerHand
+    {
+        PlayerHand { hard_total: self.hard_total + value, has_ace: self.has_ace || value == 1, num_cards: self.num_cards + 1, split: self.split }
+    }
+}
+
+pub struct Calculator
+{
+    up_card: rank::Rank,
+    rules: rules::TableRules,
+    dealer_cache: HashMap<analysis::Composition, analysis::DealerOutcomes>,
+    hit_cache: HashMap<(u32, bool, analysis::Composition), f64>,
+    fixed_dealer: bool,
+    dealer: Option<analysis::DealerOutcomes>,
+}
+
+impl Calculator
+{
+    pub fn new(up_card: rank::Rank, rules: &rules::TableRules) -> Calculator
+    {
+        Calculator { up_card, rules: *rules, dealer_cache: HashMap::new(), hit_cache: HashMap::new(), fixed_dealer: false, dealer: None }
+    }
+
+    // Much faster: the dealer only sees the cards removed before the player acts.
+    pub fn with_fixed_dealer(up_card: rank::Rank, rules: &rules::TableRules) -> Calculator
+    {
+        Calculator { fixed_dealer: true, ..Calculator::new(up_card, rules) }
+    }
+
+    pub fn dealer_outcomes(&mut self, remaining: &analysis::Composition) -> analysis::DealerOutcomes
+    {
+        if let Some(outcomes) = self.dealer_cache.get(remaining)
+        {
+            return *outcomes;
+        }
+        let outcomes = analysis::dealer_outcomes(self.up_card, remaining, &self.rules);
+        self.dealer_cache.insert(*remaining, outcomes);
+        outcomes
+    }
+
+    fn stand_ev(&mut self, hand: &PlayerHand, remaining: &analysis::Composition) -> f64
+    {
+        let total = hand.total();
+        if total > 21
+        {
+            return -1.0;
+        }
+        let dealer = match self.dealer
+        {
+            Some(dealer) => dealer,
+            None => self.dealer_outcomes(remaining),
+        };
+        if hand.num_cards == 2 && total == 21 && (!hand.split || self.rules.split.blackjack_after_split)
+        {
+            return (1.0 - dealer.blackjack) * self.rules.blackjack_payout.ratio();
+        }
+
+        let mut ev = dealer.bust - dealer.blackjack;
+        for dealer_total in 17..=21
+        {
+            ev += match dealer_total.cmp(&total)
+            {
+                std::cmp::Ordering::Less => dealer.total(dealer_total),
+                std::cmp::Ordering::Greater => -dealer.total(dealer_total),
+                std::cmp::Ordering::Equal => 0.0,
+            };
+        }
+        ev
+    }
+
+    fn hit_ev(&mut self, hand: &PlayerHand, remaining: &mut analysis::Composition) -> f64
+    {
+        let key = (hand.hard_total, hand.has_ace, *remaining);
+        if let Some(ev) = self.hit_cache.get(&key)
+        {
+            return *ev;
+        }
+        let cards_left = remaining.len() as f64;
+        let mut ev = 0.0;
+        for value in 1..=analysis::TEN
+        {
+            let count = remaining.count(value);
+            if count == 0
+            {
+                continue;
+            }
+            let probability = count as f64 / cards_left;
+            let drawn = hand.draw(value);
+            remaining.remove_value(value);
+            ev += probability * match drawn.total() > 21
+            {
+                true => -1.0,
+                false => self.stand_ev(&drawn, remaining).max(self.hit_ev(&drawn, remaining)),
+            };
+            remaining.add_value(value);
+        }
+        self.hit_cache.insert(key, ev);
+        ev
+    }
+
+    fn double_ev(&mut self, hand: &PlayerHand, remaining: &mut analysis::Composition) -> f64
+    {
+        let cards_left = remaining.len() as f64;
+        let mut ev = 0.0;
+        for value in 1..=analysis::TEN
+        {
+            let count = remaining.count(value);
+            if count == 0
+            {
+                continue;
+            }
+            remaining.remove_value(value);
+            ev += count as f64 / cards_left * 2.0 * self.stand_ev(&hand.draw(value), remaining);
+            remaining.add_value(value);
+        }
+        ev
+    }
+
+    fn best_split_hand_ev(&mut self, pair_value: u32, hand: &PlayerHand, remaining: &mut analysis::Composition) -> f64
+    {
+        let stand = self.stand_ev(hand, remaining);
+        if pair_value == 1 && !self.rules.split.hit_split_aces
+        {
+            return stand;
+        }
+        let mut ev = stand.max(self.hit_ev(hand, remaining));
+        if self.rules.double.allows_total(hand.total()) && self.rules.double.after_split
+        {
+            ev = ev.max(self.double_ev(hand, remaining));
+        }
+        ev
+    }
+
+    // Each pending hand sees the same shoe apart from the pair cards already dealt, which is the usual approximation.
+    fn split_hands_ev(&mut self, pair_value: u32, pending: u32, num_hands: u32, remaining: &mut analysis::Composition) -> f64
+    {
+        if pending == 0
+        {
+            return 0.0;
+        }
+        let can_resplit = num_hands < self.rules.split.max_hands && (pair_value != 1 || self.rules.split.resplit_aces);
+        let start = PlayerHand { hard_total: pair_value, has_ace: pair_value == 1, num_cards: 1, split: true };
+        let rest = self.split_hands_ev(pair_value, pending - 1, num_hands, remaining);
+        let cards_left = remaining.len() as f64;
+        let mut ev = 0.0;
+        for value in 1..=analysis::TEN
+        {
+            let count = remaining.count(value);
+            if count == 0
+            {
+                continue;
+            }
+            remaining.remove_value(value);
+            let play = self.best_split_hand_ev(pair_value, &start.draw(value), remaining) + rest;
+            ev += count as f64 / cards_left * match value == pair_value && can_resplit
+            {
+                true => play.max(self.split_hands_ev(pair_value, pending + 1, num_hands + 1, remaining)),
+                false => play,
+            };
+            remaining.add_value(value);
+        }
+        ev
+    }
+
+    fn surrender_ev(&self, remaining: &analysis::Composition) -> f64
+    {
+        let hole_card = analysis::blackjack_hole_card(analysis::point_value(self.up_card));
+        let dealer_blackjack = hole_card.map_or(0.0, |hole_card| remaining.probability(hole_card));
+        match (self.rules.surrender, self.rules.dealer_peeks)
+        {
+            // Without a peek the dealer's blackjack is only found after play and still takes the whole bet.
+            (rules::SurrenderRule::Late, false) => -0.5 * (1.0 - dealer_blackjack) - dealer_blackjack,
+            // Early surrender comes before the peek, so weigh it against the other actions, which assume no blackjack.
+            (rules::SurrenderRule::Early, true) if dealer_blackjack < 1.0 => (dealer_blackjack - 0.5) / (1.0 - dealer_blackjack),
+            _ => -0.5,
+        }
+    }
+
+    pub fn action_evs(&mut self, hand: &hand::Hand, remaining: &analysis::Composition) -> ActionEvs
+    {
+        let mut remaining = *remaining;
+        if self.fixed_dealer
+        {
+            self.dealer = Some(self.dealer_outcomes(&remaining));
+            self.hit_cache.clear();
+        }
+        let cards = hand.cards();
+        let player = PlayerHand {
+            hard_total: cards.iter().map(|card| card.get_point_value()).sum(),
+            has_ace: cards.iter().any(|card| card.rank == rank::Rank::Ace),
+            num_cards: cards.len() as u32,
+            split: hand.is_split(),
+        };
+
+        let split_aces = hand.is_split() && cards[0].rank == rank::Rank::Ace && !self.rules.split.hit_split_aces;
+
+        let stand = self.stand_ev(&player, &remaining);
+        let hit = match player.total() < 21 && !split_aces
+        {
+            true => Some(self.hit_ev(&player, &mut remaining)),
+            false => None,
+        };
+        let can_double = cards.len() == 2 && self.rules.double.allows_total(player.total()) && (!hand.is_split() || self.rules.double.after_split);
+        let double = match can_double && !split_aces
+        {
+            true => Some(self.double_ev(&player, &mut remaining)),
+            false => None,
+        };
+        let split = match hand.value().is_pair() && self.rules.split.max_hands > 1 && !hand.is_split()
+        {
+            true => Some(self.split_hands_ev(cards[0].get_point_value(), 2, 2, &mut remaining)),
+            false => None,
+        };
+        let surrender = match cards.len() == 2 && !hand.is_split() && self.rules.surrender != rules::SurrenderRule::NoSurrender
+        {
+            true => Some(self.surrender_ev(&remaining)),
+            false => None,
+        };
+        ActionEvs { stand, hit, double, split, surrender }
+    }
+}
+
+pub fn action_evs(hand: &hand::Hand, up_card: rank::Rank, remaining: &analysis::Composition, rules: &rules::TableRules) -> ActionEvs
+{
+    Calculator::new(up_card, rules).action_evs(hand, remaining)
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::card;
+
+    fn evs_for(player: &str, up_card: &str, rules: &rules::TableRules) -> ActionEvs
+    {
+        let mut hand = hand::Hand::new();
+        let mut remaining = analysis::Composition::with_decks(rules.decks);
+        for card in player.split_whitespace()
+        {
+            let card: card::Card = card.parse().unwrap();
+            assert!(remaining.remove(&card));
+            hand.add_card_to_hand(card);
+        }
+        let up_card: card::Card = up_card.parse().unwrap();
+        assert!(remaining.remove(&up_card));
+        action_evs(&hand, up_card.rank, &remaining, rules)
+    }
+
+    #[test]
+    fn test_sixteen_against_nine()
+    {
+        let rules = rules::TableRules::vegas_strip();
+        let evs = evs_for("TH 6S", "9C", &rules);
+
+        assert!((evs.stand - -0.5425).abs() < 0.001, "{}", evs);
+        assert!((evs.hit.unwrap() - -0.5045).abs() < 0.001, "{}", evs);
+        assert!(evs.double.unwrap() < evs.hit.unwrap());
+        assert_eq!(evs.split, None);
+        assert_eq!(evs.surrender, None);
+        assert_eq!(evs.best().0, BlackjackAction::Hit);
+    }
+
+    #[test]
+    fn test_eleven_doubles_and_twenty_stands()
+    {
+        let rules = rules::TableRules::vegas_strip();
+
+        assert_eq!(evs_for("6H 5S", "6C", &rules).best().0, BlackjackAction::Double);
+        assert_eq!(evs_for("TH KS", "TC", &rules).best().0, BlackjackAction::Stay);
+        assert_eq!(evs_for("TH KS", "TC", &rules).hit.map(|hit| hit < -0.8), Some(true));
+        assert_eq!(evs_for("8H 8S", "TC", &rules).best().0, BlackjackAction::Split);
+        assert_eq!(evs_for("AH AS", "6C", &rules).best().0, BlackjackAction::Split);
+    }
+
+    #[test]
+    fn test_surrender_and_blackjack()
+    {
+        let rules = rules::TableRules { surrender: rules::SurrenderRule::Late, ..rules::TableRules::vegas_strip() };
+        let evs = evs_for("TH 6S", "AC", &rules);
+        assert_eq!(evs.surrender, Some(-0.5));
+        assert_eq!(evs.best().0, BlackjackAction::Surrender);
+
+        let no_peek = rules::TableRules { dealer_peeks: false, ..rules };
+        assert!((evs_for("TH 6S", "AC", &no_peek).surrender.unwrap() - (-0.5 - 0.5 * 95.0 / 309.0)).abs() < 1e-9);
+        assert_eq!(evs_for("TH 6S", "9C", &no_peek).surrender, Some(-0.5));
+
+        let early = rules::TableRules { surrender: rules::SurrenderRule::Early, ..rules };
+        let dealer_blackjack = 95.0 / 309.0;
+        assert!((evs_for("TH 6S", "AC", &early).surrender.unwrap() - (dealer_blackjack - 0.5) / (1.0 - dealer_blackjack)).abs() < 1e-9);
+        assert_eq!(evs_for("TH 6S", "AC", &rules::TableRules { dealer_peeks: false, ..early }).surrender, Some(-0.5));
+
+        let evs = evs_for("AH KS", "9C", &rules::TableRules::vegas_strip());
+        assert_eq!(evs.stand, 1.5);
+        assert_eq!(evs.hit, None);
+
+        let evs = evs_for("AH KS", "TC", &rules::TableRules::european());
+        assert!((evs.stand - 1.5 * (1.0 - 23.0 / 309.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_no_hole_card_loses_doubles_to_blackjack()
+    {
+        let peek = evs_for("6H 5S", "TC", &rules::TableRules::vegas_strip());
+        let no_peek = evs_for("6H 5S", "TC", &rules::TableRules { dealer_peeks: false, ..rules::TableRules::vegas_strip() });
+
+        assert!(no_peek.double.unwrap() < peek.double.unwrap());
+        assert!(no_peek.stand < peek.stand);
+    }
+
+    #[test]
+    fn test_split_rules()
+    {
+        let rules = rules::TableRules::vegas_strip();
+        let no_resplit = rules::TableRules { split: rules::SplitRules { max_hands: 2, ..rules.split }, ..rules };
+        let no_das = rules::TableRules { double: rules::DoubleRules { after_split: false, ..rules.double }, ..rules };
+
+        let resplit = evs_for("8H 8S", "6C", &rules).split.unwrap();
+        assert!(resplit > evs_for("8H 8S", "6C", &no_resplit).split.unwrap());
+        assert!(resplit > evs_for("8H 8S", "6C", &no_das).split.unwrap());
+        assert_eq!(evs_for("8H 8S", "6C", &rules::TableRules { split: rules::SplitRules::no_splits(), ..rules }).split, None);
+    }
+
+    #[test]
+    fn test_resplits_stop_at_max_hands()
+    {
+        let mut hand = hand::Hand::new();
+        hand.add_card_to_hand("8H".parse().unwrap());
+        hand.add_card_to_hand("8S".parse().unwrap());
+        let eights = analysis::Composition::from_cards(&["8C".parse().unwrap(); 20]);
+        for max_hands in 2..=4
+        {
+            let rules = rules::TableRules { split: rules::SplitRules { max_hands, ..rules::SplitRules::default() }, ..rules::TableRules::vegas_strip() };
+            let evs = action_evs(&hand, rank::Rank::Six, &eights, &rules);
+            assert_eq!(evs.split, Some(max_hands as f64));
+            let evs = action_evs(&hand, rank::Rank::Ten, &eights, &rules);
+            assert_eq!(evs.split, Some(-2.0));
+        }
+    }
+}
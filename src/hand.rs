@@ -4,35 +4,103 @@ use std::vec;
 use crate::rank;
 use crate::card;
 
-#[derive(Debug)]
-pub struct Hand(Vec<card::Card>);
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct HandValue
+{
+    total: u32,
+    soft: bool,
+    blackjack: bool,
+    pair: bool,
+}
+
+impl fmt::Display for HandValue
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match (self.blackjack, self.soft)
+        {
+            (true, _) => write!(f, "Blackjack"),
+            (false, true) => write!(f, "soft {}", self.total),
+            (false, false) => write!(f, "{}", self.total),
+        }
+    }
+}
+
+impl HandValue
+{
+    pub fn total(&self) -> u32
+    {
+        self.total
+    }
+
+    pub fn is_soft(&self) -> bool
+    {
+        self.soft
+    }
+
+    pub fn is_blackjack(&self) -> bool
+    {
+        self.blackjack
+    }
+
+    pub fn is_bust(&self) -> bool
+    {
+        self.total > 21
+    }
+
+    pub fn is_pair(&self) -> bool
+    {
+        self.pair
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Hand
+{
+    cards: Vec<card::Card>,
+    split: bool,
+    doubled: bool,
+    surrendered: bool,
+}
 
 impl fmt::Display for Hand
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
     {
         write!(f, "[ ")?;
-        for (index, card) in self.0.iter().enumerate()
+        for (index, card) in self.cards.iter().enumerate()
         {
             if index != 0 { write!(f, ", ")?; }
-            write!(f, "{}", card)?;
+            match f.alternate()
+            {
+                true => write!(f, "{:#}", card)?,
+                false => write!(f, "{}", card)?,
+            }
         }
         write!(f, " ]")
     }
 }
 
+impl Default for Hand
+{
+    fn default() -> Self
+    {
+        Hand::new()
+    }
+}
+
 impl Hand
 {
     pub fn new() -> Hand
     {
-        Hand(vec![])
+        Hand { cards: vec![], split: false, doubled: false, surrendered: false }
     }
 
-    pub fn get_point_value(&self) -> u32
+    pub fn value(&self) -> HandValue
     {
         let mut points: u32 = 0;
         let mut aces: u32 = 0;
-        for card in self.0.iter()
+        for card in self.cards.iter()
         {
             points += card.get_point_value();
             if card.rank == rank::Rank::Ace
@@ -46,22 +114,73 @@ impl Hand
             points -= 10;
             aces -= 1;
         }
-        points
+
+        HandValue {
+            total: points,
+            soft: aces > 0,
+            blackjack: points == 21 && self.cards.len() == 2 && !self.split,
+            pair: self.cards.len() == 2 && self.cards[0].get_point_value() == self.cards[1].get_point_value(),
+        }
+    }
+
+    pub fn get_point_value(&self) -> u32
+    {
+        self.value().total()
     }
 
     pub fn get_num_cards(&self) -> u32
     {
-        self.0.len() as u32
+        self.cards.len() as u32
+    }
+
+    pub fn cards(&self) -> &[card::Card]
+    {
+        &self.cards
+    }
+
+    pub fn is_split(&self) -> bool
+    {
+        self.split
+    }
+
+    pub fn is_doubled(&self) -> bool
+    {
+        self.doubled
+    }
+
+    pub fn is_surrendered(&self) -> bool
+    {
+        self.surrendered
     }
 
     pub fn add_card_to_hand(&mut self, card: card::Card)
     {
-        self.0.push(card);
+        self.cards.push(card);
+    }
+
+    pub fn double_down(&mut self, card: card::Card)
+    {
+        self.doubled = true;
+        self.cards.push(card);
+    }
+
+    pub fn surrender(&mut self)
+    {
+        self.surrendered = true;
+    }
+
+    pub fn split(&mut self) -> Hand
+    {
+        self.split = true;
+        Hand { cards: self.cards.drain(1..).collect(), split: true, doubled: false, surrendered: false }
     }
 
     pub fn discard_hand(&mut self) -> Vec<card::Card>
     {
-        let discard: Vec<card::Card> = self.0.drain(..).collect();
+        self.split = false;
+        self.doubled = false;
+        self.surrendered = false;
+        let discard: Vec<card::Card> = self.cards.drain(..).collect();
         discard
     }
 }
@@ -94,6 +213,8 @@ mod tests
         assert_eq!(formatted_string, "[ Ace of Spades, Jack of Clubs ]");
         let formatted_string = format!("{}", three_card_hand);
         assert_eq!(formatted_string, "[ Ace of Spades, Jack of Clubs, 2 of Diamonds ]");
+        let formatted_string = format!("{:#}", three_card_hand);
+        assert_eq!(formatted_string, "[ A♠, J♣, 2♦ ]");
     }
 
     #[test]
@@ -101,7 +222,7 @@ mod tests
     {
         let empty_hand = Hand::new();
 
-        assert_eq!(empty_hand.0, []);
+        assert_eq!(empty_hand.cards, []);
     }
 
     #[test]
@@ -138,22 +259,22 @@ mod tests
         three_card_hand.add_card_to_hand(card::Card { rank: rank::Rank::Jack, suit: suit::Suit::Clubs});
         three_card_hand.add_card_to_hand(card::Card { rank: rank::Rank::Two, suit: suit::Suit::Diamonds});
 
-        assert!(!empty_hand.0.contains(&card::Card { rank: rank::Rank::Ace, suit: suit::Suit::Spades}));
-        assert!(!empty_hand.0.contains(&card::Card { rank: rank::Rank::Jack, suit: suit::Suit::Clubs}));
-        assert!(!empty_hand.0.contains(&card::Card { rank: rank::Rank::Two, suit: suit::Suit::Diamonds}));
-        assert_eq!(empty_hand.0.len(), 0);
-        assert!(one_card_hand.0.contains(&card::Card { rank: rank::Rank::Ace, suit: suit::Suit::Spades}));
-        assert!(!one_card_hand.0.contains(&card::Card { rank: rank::Rank::Jack, suit: suit::Suit::Clubs}));
-        assert!(!one_card_hand.0.contains(&card::Card { rank: rank::Rank::Two, suit: suit::Suit::Diamonds}));
-        assert_eq!(one_card_hand.0.len(), 1);
-        assert!(two_card_hand.0.contains(&card::Card { rank: rank::Rank::Ace, suit: suit::Suit::Spades}));
-        assert!(two_card_hand.0.contains(&card::Card { rank: rank::Rank::Jack, suit: suit::Suit::Clubs}));
-        assert!(!two_card_hand.0.contains(&card::Card { rank: rank::Rank::Two, suit: suit::Suit::Diamonds}));
-        assert_eq!(two_card_hand.0.len(), 2);
-        assert!(three_card_hand.0.contains(&card::Card { rank: rank::Rank::Ace, suit: suit::Suit::Spades}));
-        assert!(three_card_hand.0.contains(&card::Card { rank: rank::Rank::Jack, suit: suit::Suit::Clubs}));
-        assert!(three_card_hand.0.contains(&card::Card { rank: rank::Rank::Two, suit: suit::Suit::Diamonds}));
-        assert_eq!(three_card_hand.0.len(), 3);
+        assert!(!empty_hand.cards.contains(&card::Card { rank: rank::Rank::Ace, suit: suit::Suit::Spades}));
+        assert!(!empty_hand.cards.contains(&card::Card { rank: rank::Rank::Jack, suit: suit::Suit::Clubs}));
+        assert!(!empty_hand.cards.contains(&card::Card { rank: rank::Rank::Two, suit: suit::Suit::Diamonds}));
+        assert_eq!(empty_hand.cards.len(), 0);
+        assert!(one_card_hand.cards.contains(&card::Card { rank: rank::Rank::Ace, suit: suit::Suit::Spades}));
+        assert!(!one_card_hand.cards.contains(&card::Card { rank: rank::Rank::Jack, suit: suit::Suit::Clubs}));
+        assert!(!one_card_hand.cards.contains(&card::Card { rank: rank::Rank::Two, suit: suit::Suit::Diamonds}));
+        assert_eq!(one_card_hand.cards.len(), 1);
+        assert!(two_card_hand.cards.contains(&card::Card { rank: rank::Rank::Ace, suit: suit::Suit::Spades}));
+        assert!(two_card_hand.cards.contains(&card::Card { rank: rank::Rank::Jack, suit: suit::Suit::Clubs}));
+        assert!(!two_card_hand.cards.contains(&card::Card { rank: rank::Rank::Two, suit: suit::Suit::Diamonds}));
+        assert_eq!(two_card_hand.cards.len(), 2);
+        assert!(three_card_hand.cards.contains(&card::Card { rank: rank::Rank::Ace, suit: suit::Suit::Spades}));
+        assert!(three_card_hand.cards.contains(&card::Card { rank: rank::Rank::Jack, suit: suit::Suit::Clubs}));
+        assert!(three_card_hand.cards.contains(&card::Card { rank: rank::Rank::Two, suit: suit::Suit::Diamonds}));
+        assert_eq!(three_card_hand.cards.len(), 3);
     }
 
     #[test]
@@ -184,7 +305,7 @@ mod tests
         three_card_hand.add_card_to_hand(card::Card { rank: rank::Rank::Jack, suit: suit::Suit::Clubs});
         three_card_hand.add_card_to_hand(card::Card { rank: rank::Rank::Two, suit: suit::Suit::Diamonds});
 
-        assert_eq!(three_card_hand.0.len(), 3);
+        assert_eq!(three_card_hand.cards.len(), 3);
 
         let discarded_hand = three_card_hand.discard_hand();
 
@@ -192,6 +313,136 @@ mod tests
             card::Card { rank: rank::Rank::Jack, suit: suit::Suit::Clubs},
             card::Card { rank: rank::Rank::Two, suit: suit::Suit::Diamonds}]);
 
-        assert_eq!(three_card_hand.0.len(), 0);
+        assert_eq!(three_card_hand.cards.len(), 0);
+    }
+
+    #[test]
+    fn test_hand_value_is_pair()
+    {
+        let mut aces = Hand::new();
+        aces.add_card_to_hand(card::Card { rank: rank::Rank::Ace, suit: suit::Suit::Spades});
+        assert!(!aces.value().is_pair());
+        aces.add_card_to_hand(card::Card { rank: rank::Rank::Ace, suit: suit::Suit::Hearts});
+        assert!(aces.value().is_pair());
+        aces.add_card_to_hand(card::Card { rank: rank::Rank::Ace, suit: suit::Suit::Clubs});
+        assert!(!aces.value().is_pair());
+
+        let mut tens = Hand::new();
+        tens.add_card_to_hand(card::Card { rank: rank::Rank::Jack, suit: suit::Suit::Spades});
+        tens.add_card_to_hand(card::Card { rank: rank::Rank::King, suit: suit::Suit::Hearts});
+        assert!(tens.value().is_pair());
+
+        let mut no_pair = Hand::new();
+        no_pair.add_card_to_hand(card::Card { rank: rank::Rank::Eight, suit: suit::Suit::Spades});
+        no_pair.add_card_to_hand(card::Card { rank: rank::Rank::Nine, suit: suit::Suit::Hearts});
+        assert!(!no_pair.value().is_pair());
+    }
+
+    #[test]
+    fn test_split_hand()
+    {
+        let mut eights = Hand::new();
+        eights.add_card_to_hand(card::Card { rank: rank::Rank::Eight, suit: suit::Suit::Spades});
+        eights.add_card_to_hand(card::Card { rank: rank::Rank::Eight, suit: suit::Suit::Hearts});
+        assert!(!eights.is_split());
+
+        let second_hand = eights.split();
+
+        assert!(eights.is_split());
+        assert!(second_hand.is_split());
+        assert_eq!(eights.cards(), [card::Card { rank: rank::Rank::Eight, suit: suit::Suit::Spades}]);
+        assert_eq!(second_hand.cards(), [card::Card { rank: rank::Rank::Eight, suit: suit::Suit::Hearts}]);
+
+        eights.discard_hand();
+        assert!(!eights.is_split());
+    }
+
+    #[test]
+    fn test_double_down()
+    {
+        let mut hand = Hand::new();
+        hand.add_card_to_hand(card::Card { rank: rank::Rank::Six, suit: suit::Suit::Spades});
+        hand.add_card_to_hand(card::Card { rank: rank::Rank::Five, suit: suit::Suit::Hearts});
+        assert!(!hand.is_doubled());
+
+        hand.double_down(card::Card { rank: rank::Rank::King, suit: suit::Suit::Clubs});
+
+        assert!(hand.is_doubled());
+        assert_eq!(hand.get_num_cards(), 3);
+        assert_eq!(hand.get_point_value(), 21);
+
+        hand.discard_hand();
+        assert!(!hand.is_doubled());
+    }
+
+    #[test]
+    fn test_surrender()
+    {
+        let mut hand = Hand::new();
+        hand.add_card_to_hand(card::Card { rank: rank::Rank::Ten, suit: suit::Suit::Spades});
+        hand.add_card_to_hand(card::Card { rank: rank::Rank::Six, suit: suit::Suit::Hearts});
+        assert!(!hand.is_surrendered());
+
+        hand.surrender();
+
+        assert!(hand.is_surrendered());
+        assert_eq!(hand.get_num_cards(), 2);
+
+        hand.discard_hand();
+        assert!(!hand.is_surrendered());
+    }
+
+    #[test]
+    fn test_hand_value_soft_and_hard()
+    {
+        let mut hand = Hand::new();
+        hand.add_card_to_hand(card::Card { rank: rank::Rank::Ace, suit: suit::Suit::Spades});
+        hand.add_card_to_hand(card::Card { rank: rank::Rank::Six, suit: suit::Suit::Hearts});
+        assert_eq!(hand.value().total(), 17);
+        assert!(hand.value().is_soft());
+        assert!(!hand.value().is_bust());
+        assert_eq!(format!("{}", hand.value()), "soft 17");
+
+        hand.add_card_to_hand(card::Card { rank: rank::Rank::Ten, suit: suit::Suit::Clubs});
+        assert_eq!(hand.value().total(), 17);
+        assert!(!hand.value().is_soft());
+        assert_eq!(format!("{}", hand.value()), "17");
+
+        hand.add_card_to_hand(card::Card { rank: rank::Rank::Ace, suit: suit::Suit::Clubs});
+        hand.add_card_to_hand(card::Card { rank: rank::Rank::Five, suit: suit::Suit::Clubs});
+        assert_eq!(hand.value().total(), 23);
+        assert!(hand.value().is_bust());
+
+        let mut aces = Hand::new();
+        aces.add_card_to_hand(card::Card { rank: rank::Rank::Ace, suit: suit::Suit::Spades});
+        aces.add_card_to_hand(card::Card { rank: rank::Rank::Ace, suit: suit::Suit::Hearts});
+        assert_eq!(aces.value().total(), 12);
+        assert!(aces.value().is_soft());
+    }
+
+    #[test]
+    fn test_hand_value_is_blackjack()
+    {
+        let mut hand = Hand::new();
+        hand.add_card_to_hand(card::Card { rank: rank::Rank::Ace, suit: suit::Suit::Spades});
+        hand.add_card_to_hand(card::Card { rank: rank::Rank::Ace, suit: suit::Suit::Hearts});
+        assert!(!hand.value().is_blackjack());
+
+        let mut second_hand = hand.split();
+        hand.add_card_to_hand(card::Card { rank: rank::Rank::King, suit: suit::Suit::Hearts});
+        second_hand.add_card_to_hand(card::Card { rank: rank::Rank::Nine, suit: suit::Suit::Hearts});
+        assert_eq!(hand.value().total(), 21);
+        assert!(!hand.value().is_blackjack());
+
+        let mut natural = Hand::new();
+        natural.add_card_to_hand(card::Card { rank: rank::Rank::Queen, suit: suit::Suit::Spades});
+        natural.add_card_to_hand(card::Card { rank: rank::Rank::Ace, suit: suit::Suit::Diamonds});
+        assert!(natural.value().is_blackjack());
+        assert!(natural.value().is_soft());
+        assert_eq!(format!("{}", natural.value()), "Blackjack");
+
+        natural.add_card_to_hand(card::Card { rank: rank::Rank::Ten, suit: suit::Suit::Diamonds});
+        assert!(!natural.value().is_blackjack());
+        assert_eq!(natural.value().total(), 21);
     }
 }
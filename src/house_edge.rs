@@ -0,0 +1,223 @@
+use std::fmt;
+
+use crate::analysis;
+use crate::card;
+use crate::ev;
+use crate::hand;
+use crate::rank;
+use crate::rules;
+use crate::suit;
+
+fn card_of(value: u32) -> card::Card
+{
+    card::Card { rank: rank::Rank::try_from(value).unwrap(), suit: suit::Suit::Spades }
+}
+
+fn initial_hand_ev(calculator: &mut ev::Calculator, hand: &hand::Hand, up_value: u32, remaining: &analysis::Composition, rules: &rules::TableRules) -> f64
+{
+    let (_, best) = calculator.action_evs(hand, remaining).best();
+    match (rules.dealer_peeks, analysis::blackjack_hole_card(up_value))
+    {
+        (true, Some(hole_card)) => {
+            let dealer_blackjack = remaining.probability(hole_card);
+            let player_blackjack = hand.value().is_blackjack();
+            dealer_blackjack * if player_blackjack { 0.0 } else { -1.0 } + (1.0 - dealer_blackjack) * best
+        }
+        _ => best,
+    }
+}
+
+pub fn expected_value(rules: &rules::TableRules) -> f64
+{
+    let shoe = analysis::Composition::with_decks(rules.decks);
+    let mut total = 0.0;
+    for up_value in 1..=analysis::TEN
+    {
+        let mut after_up_card = shoe;
+        after_up_card.remove_value(up_value);
+        let mut calculator = ev::Calculator::with_fixed_dealer(card_of(up_value).rank, rules);
+        for first in 1..=analysis::TEN
+        {
+            for second in first..=analysis::TEN
+            {
+                let mut remaining = after_up_card;
+                let first_probability = remaining.probability(first);
+                remaining.remove_value(first);
+                let mut probability = first_probability * remaining.probability(second);
+                remaining.remove_value(second);
+                if first != second
+                {
+                    probability *= 2.0;
+                }
+                if probability == 0.0
+                {
+                    continue;
+                }
+
+                let mut hand = hand::Hand::new();
+                hand.add_card_to_hand(card_of(first));
+                hand.add_card_to_hand(card_of(second));
+                total += shoe.probability(up_value) * probability * initial_hand_ev(&mut calculator, &hand, up_value, &remaining, rules);
+            }
+        }
+    }
+    total
+}
+
+pub fn house_edge(rules: &rules::TableRules) -> f64
+{
+    -expected_value(rules)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleContribution
+{
+    pub rule: String,
+    pub edge: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HouseEdgeReport
+{
+    pub rules: rules::TableRules,
+    pub baseline_edge: f64,
+    pub contributions: Vec<RuleContribution>,
+    pub house_edge: f64,
+}
+
+fn describe_surrender(surrender: rules::SurrenderRule) -> &'static str
+{
+    match surrender
+    {
+        rules::SurrenderRule::NoSurrender => "no surrender",
+        rules::SurrenderRule::Late => "late surrender",
+        rules::SurrenderRule::Early => "early surrender",
+    }
+}
+
+fn describe_double(on: rules::DoubleOn) -> &'static str
+{
+    match on
+    {
+        rules::DoubleOn::AnyTwoCards => "double any two cards",
+        rules::DoubleOn::NineToEleven => "double 9-11 only",
+        rules::DoubleOn::TenToEleven => "double 10-11 only",
+        rules::DoubleOn::Never => "no doubling",
+    }
+}
+
+fn rule_changes(baseline: &rules::TableRules, target: &rules::TableRules) -> Vec<(String, rules::TableRules)>
+{
+    let mut changes = vec![];
+    let mut change = |rule: String, changed: rules::TableRules| {
+        if changed != *baseline
+        {
+            changes.push((rule, changed));
+        }
+    };
+
+    change(format!("{} deck(s)", target.decks), rules::TableRules { decks: target.decks, ..*baseline });
+    change(String::from(if target.dealer_hits_soft_17 { "dealer hits soft 17" } else { "dealer stands on soft 17" }), rules::TableRules { dealer_hits_soft_17: target.dealer_hits_soft_17, ..*baseline });
+    change(format!("Blackjack pays {}", target.blackjack_payout), rules::TableRules { blackjack_payout: target.blackjack_payout, ..*baseline });
+    change(String::from(if target.dealer_peeks { "dealer peeks" } else { "no hole card" }), rules::TableRules { dealer_peeks: target.dealer_peeks, ..*baseline });
+    change(String::from(describe_double(target.double.on)), rules::TableRules { double: rules::DoubleRules { on: target.double.on, ..baseline.double }, ..*baseline });
+    change(String::from(if target.double.after_split { "double after split" } else { "no double after split" }), rules::TableRules { double: rules::DoubleRules { after_split: target.double.after_split, ..baseline.double }, ..*baseline });
+    change(format!("split to {} hands", target.split.max_hands), rules::TableRules { split: rules::SplitRules { max_hands: target.split.max_hands, ..baseline.split }, ..*baseline });
+    change(String::from(if target.split.resplit_aces { "resplit aces" } else { "no resplitting aces" }), rules::TableRules { split: rules::SplitRules { resplit_aces: target.split.resplit_aces, ..baseline.split }, ..*baseline });
+    change(String::from(if target.split.hit_split_aces { "hit split aces" } else { "one card to split aces" }), rules::TableRules { split: rules::SplitRules { hit_split_aces: target.split.hit_split_aces, ..baseline.split }, ..*baseline });
+    change(String::from(if target.split.blackjack_after_split { "blackjack after split pays" } else { "21 after split is not blackjack" }), rules::TableRules { split: rules::SplitRules { blackjack_after_split: target.split.blackjack_after_split, ..baseline.split }, ..*baseline });
+    change(String::from(describe_surrender(target.surrender)), rules::TableRules { surrender: target.surrender, ..*baseline });
+    changes
+}
+
+pub const APPROXIMATION: &str = "Approximate: the dealer's odds use the shoe after the first three cards and ignore the player's draws.";
+
+pub const PRESETS: [&str; 4] = ["vegas", "atlantic-city", "downtown", "european"];
+
+pub fn compare_presets() -> Vec<(&'static str, rules::TableRules, f64)>
+{
+    PRESETS.iter().filter_map(|name| rules::TableRules::preset(name).map(|rules| (*name, rules, house_edge(&rules)))).collect()
+}
+
+pub fn report(rules: &rules::TableRules) -> HouseEdgeReport
+{
+    let baseline = rules::TableRules::vegas_strip();
+    let baseline_edge = house_edge(&baseline);
+    let contributions = rule_changes(&baseline, rules).into_iter()
+        .map(|(rule, changed)| RuleContribution { rule, edge: house_edge(&changed) - baseline_edge })
+        .collect();
+    HouseEdgeReport { rules: *rules, baseline_edge, contributions, house_edge: house_edge(rules) }
+}
+
+impl fmt::Display for HouseEdgeReport
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        writeln!(f, "Table rules: {}", self.rules)?;
+        writeln!(f, "{:<36} {:+.3}%", "Baseline (Vegas Strip rules)", self.baseline_edge * 100.0)?;
+        for contribution in self.contributions.iter()
+        {
+            writeln!(f, "{:<36} {:+.3}%", contribution.rule, contribution.edge * 100.0)?;
+        }
+        let interactions = self.house_edge - self.baseline_edge - self.contributions.iter().map(|contribution| contribution.edge).sum::<f64>();
+        if !self.contributions.is_empty()
+        {
+            writeln!(f, "{:<36} {:+.3}%", "Rule interactions", interactions * 100.0)?;
+        }
+        writeln!(f, "{:<36} {:+.3}%", "House edge", self.house_edge * 100.0)?;
+        write!(f, "{}", APPROXIMATION)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn test_vegas_strip_house_edge()
+    {
+        let edge = house_edge(&rules::TableRules::vegas_strip());
+        assert!(edge > 0.0025 && edge < 0.0050, "{}", edge);
+    }
+
+    #[test]
+    fn test_rule_contributions()
+    {
+        let rules = rules::TableRules { blackjack_payout: rules::BlackjackPayout::SixToFive, dealer_hits_soft_17: true, ..rules::TableRules::vegas_strip() };
+        let report = report(&rules);
+
+        assert_eq!(report.contributions.len(), 2);
+        assert_eq!(report.contributions[0].rule, "dealer hits soft 17");
+        assert!(report.contributions[0].edge > 0.0015 && report.contributions[0].edge < 0.0030, "{}", report);
+        assert_eq!(report.contributions[1].rule, "Blackjack pays 6:5");
+        assert!((report.contributions[1].edge - 0.0139).abs() < 0.001, "{}", report);
+        assert!(report.house_edge > report.baseline_edge + report.contributions[1].edge);
+        assert!(format!("{}", report).contains("Blackjack pays 6:5"));
+        assert!(format!("{}", report).ends_with(APPROXIMATION));
+    }
+
+    #[test]
+    fn test_rule_changes()
+    {
+        let baseline = rules::TableRules::vegas_strip();
+        assert!(rule_changes(&baseline, &baseline).is_empty());
+
+        let changes: Vec<String> = rule_changes(&baseline, &rules::TableRules::european()).into_iter().map(|(rule, _)| rule).collect();
+        assert_eq!(changes, vec!["no hole card", "double 9-11 only", "split to 2 hands"]);
+
+        let changes: Vec<String> = rule_changes(&baseline, &rules::TableRules::atlantic_city()).into_iter().map(|(rule, _)| rule).collect();
+        assert_eq!(changes, vec!["8 deck(s)", "split to 2 hands", "late surrender"]);
+    }
+
+    #[test]
+    fn test_compare_presets()
+    {
+        let presets = compare_presets();
+
+        assert_eq!(presets.len(), PRESETS.len());
+        assert_eq!(presets[0].1, rules::TableRules::vegas_strip());
+        let european = presets.iter().find(|(name, _, _)| *name == "european").unwrap();
+        assert!(european.2 > presets[0].2);
+    }
+}
@@ -0,0 +1,17 @@
+pub mod suit;
+pub mod rank;
+pub mod card;
+pub mod hand;
+pub mod deck;
+pub mod shoe;
+pub mod player;
+pub mod outcome;
+pub mod rules;
+pub mod table;
+pub mod bankroll;
+pub mod strategy;
+pub mod count;
+pub mod sim;
+pub mod analysis;
+pub mod ev;
+pub mod house_edge;
@@ -1,15 +1,23 @@
+use std::env;
 use std::io;
 use std::io::Write;
 use std::io::BufRead;
-
-mod suit;
-mod rank;
-mod card;
-mod hand;
-mod player;
-mod deck;
-
-use crate::player::BlackjackPlayer;
+use std::process;
+
+use rand::Rng;
+
+use blackjack::analysis;
+use blackjack::card;
+use blackjack::count;
+use blackjack::ev;
+use blackjack::house_edge;
+use blackjack::hand;
+use blackjack::outcome;
+use blackjack::player;
+use blackjack::rules;
+use blackjack::strategy;
+use blackjack::table;
+use blackjack::player::BlackjackPlayer;
 
 fn ask_play_blackjack(first_game: bool) -> bool
 {
@@ -28,7 +36,7 @@ fn ask_play_blackjack_get_response<R: io::Read, W: io::Write>(first_game: bool,
         false => write!(writer, "Keep playing Blackjack? (yes/no) "),
     };
     writer.flush().unwrap();
-    while choice == None && tries_remaining > 0
+    while choice.is_none() && tries_remaining > 0
     {
         input.clear();
         let _ = reader.read_line(&mut input);
@@ -58,138 +66,427 @@ fn ask_play_blackjack_get_response<R: io::Read, W: io::Write>(first_game: bool,
     choice.or_else(fallback).unwrap()
 }
 
-fn main()
+fn print_hand_result(player: &dyn BlackjackPlayer, dealer: &player::Dealer, hand_name: &str, hand_result: &table::HandResult, dealer_hand: &hand::Hand)
 {
-    let mut deck = deck::Deck::new();
-    // Conditionally print deck
-    let mut play_blackjack = ask_play_blackjack(true);
-    // println!("Play Blackjack choice: {}", play_blackjack);
+    let player_points = hand_result.hand.value();
+    let dealer_points = dealer_hand.value();
 
-    while play_blackjack
+    match hand_result.outcome
     {
-        let mut dealer: player::Dealer = player::Dealer::new("Dealer");
-        let mut player: player::HumanPlayer = player::HumanPlayer::new("Player 1");
+        outcome::Outcome::PlayerBlackjack => println!("{0} got BLACKJACK!!! {1} WINS!!!", hand_name, player.name().to_uppercase()),
+        outcome::Outcome::DealerBlackjack => println!("{0} got BLACKJACK!!! {0} WINS!!!", dealer.name()),
+        outcome::Outcome::PlayerBust => {
+            println!("{} Total: {}", hand_name, player_points);
+            println!("BUST! You lost this round.");
+        }
+        outcome::Outcome::DealerBust => {
+            println!("{} Total: {}", hand_name, player_points);
+            println!("{} Total: {}", dealer.name(), dealer_points);
+            println!("{} BUSTS! {} wins this round!", dealer.name(), player.name());
+        }
+        outcome::Outcome::PlayerWin => {
+            println!("{} Total: {}", hand_name, player_points);
+            println!("{} Total: {}", dealer.name(), dealer_points);
+            println!("{} WINS!!!", player.name());
+        }
+        outcome::Outcome::DealerWin => {
+            println!("{} Total: {}", hand_name, player_points);
+            println!("{} Total: {}", dealer.name(), dealer_points);
+            println!("{} WINS!!!", dealer.name());
+        }
+        outcome::Outcome::Push => {
+            println!("{} Total: {}", hand_name, player_points);
+            println!("{} Total: {}", dealer.name(), dealer_points);
+            println!("PUSH!");
+        }
+        outcome::Outcome::Surrender => println!("{} surrendered. Half the wager is forfeited.", hand_name),
+    };
 
-        println!("Shuffling the deck");
-        deck.shuffle();
-        // Conditionally print deck
+    match hand_result.hand.is_doubled()
+    {
+        true => println!("{}'s hand (doubled): ", hand_name),
+        false => println!("{}'s hand: ", hand_name),
+    };
+    println!("{}", hand_result.hand);
+}
 
-        println!("Dealing cards");
-        player.add_card_to_hand(deck.deal_card());
-        dealer.add_card_to_hand(deck.deal_card());
-        player.add_card_to_hand(deck.deal_card());
-        dealer.add_card_to_hand(deck.deal_card());
+fn print_round_result(player: &dyn BlackjackPlayer, dealer: &player::Dealer, result: &table::RoundResult)
+{
+    for (index, hand_result) in result.hands.iter().enumerate()
+    {
+        let hand_name = match result.hands.len()
+        {
+            1 => player.name().to_string(),
+            n => format!("{} (hand {} of {})", player.name(), index + 1, n),
+        };
+        print_hand_result(player, dealer, &hand_name, hand_result, &result.dealer_hand);
+    }
+
+    print_insurance_result(dealer, result, &mut io::BufWriter::new(io::stdout()));
+}
+
+fn print_insurance_result<W: io::Write>(dealer: &player::Dealer, result: &table::RoundResult, writer: &mut io::BufWriter<W>)
+{
+    // A blackjack that was insured took even money, which settles as a push when the dealer has blackjack too.
+    let even_money = result.hands.len() == 1 && result.hands[0].hand.value().is_blackjack();
+    let _ = match result.insurance
+    {
+        Some(_) if even_money => writeln!(writer, "Even money paid."),
+        Some(net) if net > 0.0 => writeln!(writer, "{} has BLACKJACK! Insurance pays 2:1.", dealer.name()),
+        Some(_) => writeln!(writer, "{} doesn't have Blackjack. Insurance lost.", dealer.name()),
+        None => Ok(()),
+    };
+    writer.flush().unwrap();
+}
 
-        // Conditionally print deck
+fn print_winnings(player: &player::HumanPlayer, winnings: i64)
+{
+    match winnings.cmp(&0)
+    {
+        std::cmp::Ordering::Greater => println!("{} won {} chips.", player.name(), winnings),
+        std::cmp::Ordering::Less => println!("{} lost {} chips.", player.name(), -winnings),
+        std::cmp::Ordering::Equal => println!("{} broke even.", player.name()),
+    };
+    println!("{} now has {}.", player.name(), player.bankroll());
+}
 
-        let mut continue_playing = true;
-        let mut dealer_plays = true;
+fn print_coach_summary(player: &player::HumanPlayer)
+{
+    if let Some(accuracy) = player.coach_accuracy()
+    {
+        println!("{} made the basic strategy play in {:.1}% of {} decisions.", player.name(), accuracy, player.coached_decisions());
+    }
+}
 
-        while continue_playing
+#[derive(Debug, Eq, PartialEq)]
+struct Options
+{
+    seed: Option<u64>,
+    seats: usize,
+    bots: usize,
+    coach: bool,
+    show_count: bool,
+    count_system: Option<String>,
+}
+
+impl Default for Options
+{
+    fn default() -> Self
+    {
+        Options { seed: None, seats: 1, bots: 0, coach: false, show_count: false, count_system: None }
+    }
+}
+
+const PLAYER_NAMES: [&str; table::MAX_SEATS] = ["Player 1", "Player 2", "Player 3", "Player 4", "Player 5", "Player 6", "Player 7"];
+const BOT_NAMES: [&str; table::MAX_SEATS] = ["Bot 1", "Bot 2", "Bot 3", "Bot 4", "Bot 5", "Bot 6", "Bot 7"];
+
+fn counting_system(name_or_tags: &str) -> Option<Box<dyn count::CountingSystem>>
+{
+    count::system_by_name(name_or_tags).or_else(|| {
+        count::TagTable::from_notation("Custom", name_or_tags).ok().map(|tags| Box::new(tags) as Box<dyn count::CountingSystem>)
+    })
+}
+
+fn parse_options<I: Iterator<Item = String>>(mut args: I) -> Result<Options, String>
+{
+    let mut options = Options::default();
+    while let Some(arg) = args.next()
+    {
+        let (name, value) = match arg.split_once('=')
         {
-            if player.hand_under_21()
-            {
-                if player.blackjack_hand()
+            Some((name, value)) => (name, Some(value.to_string())),
+            None => (arg.as_str(), None),
+        };
+        match name
+        {
+            "--seed" => {
+                options.seed = match value.or_else(|| args.next()).map(|value| value.parse::<u64>())
                 {
-                    if dealer.blackjack_hand()
-                    {
-                        println!("PUSH!");
-                    }
-                    else
-                    {
-                        println!("{0} got BLACKJACK!!! {1} WINS!!!", player.name(), player.name().to_uppercase());
-                    }
-                    continue_playing = false;
-                    dealer_plays = false;
-                }
-                else if dealer.blackjack_hand()
+                    Some(Ok(seed)) => Some(seed),
+                    _ => return Err(String::from("--seed expects a non-negative integer")),
+                };
+            }
+            "--seats" => {
+                options.seats = match value.or_else(|| args.next()).map(|value| value.parse::<usize>())
                 {
-                    println!("{0} got BLACKJACK!!! {0} WINS!!!", dealer.name());
-                    continue_playing = false;
-                    dealer_plays = false;
-                }
-                else
+                    Some(Ok(seats)) if (1..=table::MAX_SEATS).contains(&seats) => seats,
+                    _ => return Err(format!("--seats expects a number from 1 to {}", table::MAX_SEATS)),
+                };
+            }
+            "--coach" if value.is_none() => options.coach = true,
+            "--show-count" if value.is_none() => options.show_count = true,
+            "--count-system" => {
+                options.count_system = match value.or_else(|| args.next())
                 {
-                    println!("{}'s hand: ", player.name());
-                    println!("{}", player.hand);
-                    let choice = player.hit_or_stay();
-                    let _ = match choice
-                    {
-                        player::BlackjackAction::Hit => player.add_card_to_hand(deck.deal_card()),
-                        player::BlackjackAction::Stay => {
-                            println!("{} Total: {}", player.name(), player.get_point_value());
-                            continue_playing = false;
-                        }
-                    };
-                }
+                    Some(system) if counting_system(&system).is_some() => Some(system),
+                    _ => return Err(String::from("--count-system expects hi-lo, ko, hi-opt-i, hi-opt-ii, omega-ii, zen, wong-halves, red-seven or tags like \"2=1 ... A=-1\"")),
+                };
+                options.show_count = true;
             }
-            else
-            {
-                println!("{} Total: {}", player.name(), player.get_point_value());
-                println!("BUST! You lost this round.");
-                continue_playing = false;
-                dealer_plays = false;
+            "--bots" => {
+                options.bots = match value.or_else(|| args.next()).map(|value| value.parse::<usize>())
+                {
+                    Some(Ok(bots)) => bots,
+                    _ => return Err(format!("--bots expects a number from 0 to {}", table::MAX_SEATS - 1)),
+                };
+            }
+            _ => return Err(format!("Unknown argument: {}", arg)),
+        }
+    }
+    if options.seats + options.bots > table::MAX_SEATS
+    {
+        return Err(format!("The table only has {} seats", table::MAX_SEATS));
+    }
+    Ok(options)
+}
+
+#[derive(Debug, PartialEq)]
+struct EvOptions
+{
+    player: Vec<card::Card>,
+    dealer: card::Card,
+    rules: rules::TableRules,
+}
+
+fn parse_ev_options<I: Iterator<Item = String>>(mut args: I) -> Result<EvOptions, String>
+{
+    let mut player = vec![];
+    let mut dealer = None;
+    let mut rule_options = rules::RuleOptions::default();
+    while let Some(arg) = args.next()
+    {
+        let (name, value) = match arg.split_once('=')
+        {
+            Some((name, value)) => (name, Some(value.to_string())),
+            None => (arg.as_str(), None),
+        };
+        if rule_options.parse_arg(name, value, &mut args)?
+        {
+            continue;
+        }
+        match name
+        {
+            "vs" if dealer.is_none() => {
+                dealer = match args.next().map(|card| card.parse::<card::Card>())
+                {
+                    Some(Ok(card)) => Some(card),
+                    _ => return Err(String::from("vs expects the dealer up card, like 9C")),
+                };
+            }
+            card if dealer.is_none() => player.push(card.parse().map_err(|_| format!("{} is not a card, try something like 10H or AS", card))?),
+            _ => return Err(format!("Unknown argument: {}", arg)),
+        }
+    }
+    match (player.len(), dealer)
+    {
+        (2.., Some(dealer)) => Ok(EvOptions { player, dealer, rules: rule_options.rules() }),
+        _ => Err(String::from("Give at least two player cards and the dealer up card, like: 10H 6S vs 9C")),
+    }
+}
+
+fn print_ev(options: &EvOptions) -> Result<(), String>
+{
+    let mut hand = hand::Hand::new();
+    let mut remaining = analysis::Composition::with_decks(options.rules.decks);
+    for card in options.player.iter().chain([&options.dealer])
+    {
+        if !remaining.remove(card)
+        {
+            return Err(format!("There are not enough {}s in {} deck(s)", card.rank, options.rules.decks));
+        }
+    }
+    for card in options.player.iter()
+    {
+        hand.add_card_to_hand(*card);
+    }
+
+    println!("Table rules: {}", options.rules);
+    println!("Player {:#} ({}) vs dealer {:#}", hand, hand.value(), options.dealer);
+    print!("{}", ev::action_evs(&hand, options.dealer.rank, &remaining, &options.rules));
+    Ok(())
+}
+
+#[derive(Debug, PartialEq)]
+struct EdgeOptions
+{
+    rules: rules::TableRules,
+    compare: bool,
+}
+
+fn parse_edge_options<I: Iterator<Item = String>>(mut args: I) -> Result<EdgeOptions, String>
+{
+    let mut rule_options = rules::RuleOptions::default();
+    let mut compare = false;
+    let mut payout = None;
+    while let Some(arg) = args.next()
+    {
+        let (name, value) = match arg.split_once('=')
+        {
+            Some((name, value)) => (name, Some(value.to_string())),
+            None => (arg.as_str(), None),
+        };
+        if rule_options.parse_arg(name, value.clone(), &mut args)?
+        {
+            continue;
+        }
+        match name
+        {
+            "--payout" => {
+                payout = match value.or_else(|| args.next()).as_deref()
+                {
+                    Some("3:2") => Some(rules::BlackjackPayout::ThreeToTwo),
+                    Some("6:5") => Some(rules::BlackjackPayout::SixToFive),
+                    Some("1:1") => Some(rules::BlackjackPayout::EvenMoney),
+                    _ => return Err(String::from("--payout expects 3:2, 6:5 or 1:1")),
+                };
             }
+            "--compare" if value.is_none() => compare = true,
+            _ => return Err(format!("Unknown argument: {}", arg)),
         }
+    }
+    let mut rules = rule_options.rules();
+    if let Some(payout) = payout
+    {
+        rules.blackjack_payout = payout;
+    }
+    Ok(EdgeOptions { rules, compare })
+}
+
+fn print_house_edge(options: &EdgeOptions)
+{
+    if options.compare
+    {
+        println!("{:<15} {:>10}  Rules", "Preset", "House edge");
+        for (name, rules, edge) in house_edge::compare_presets()
+        {
+            println!("{:<15} {:>9.3}%  {}", name, edge * 100.0, rules);
+        }
+        println!("{}", house_edge::APPROXIMATION);
+        return;
+    }
+    println!("{}", house_edge::report(&options.rules));
+}
 
-        while dealer_plays
+fn main()
+{
+    if env::args().nth(1).as_deref() == Some("ev")
+    {
+        let result = parse_ev_options(env::args().skip(2)).and_then(|options| print_ev(&options));
+        if let Err(error) = result
         {
-            if dealer.hand_under_21()
+            eprintln!("{}", error);
+            eprintln!("Usage: blackjack ev <cards> vs <dealer card> [--decks <1-8>] [--h17|--s17] [--rules <preset>]");
+            process::exit(2);
+        }
+        return;
+    }
+    if env::args().nth(1).as_deref() == Some("edge")
+    {
+        match parse_edge_options(env::args().skip(2))
+        {
+            Ok(options) => print_house_edge(&options),
+            Err(error) => {
+                eprintln!("{}", error);
+                eprintln!("Usage: blackjack edge [--rules <preset>] [--decks <1-8>] [--h17|--s17] [--payout <3:2|6:5|1:1>] [--compare]");
+                process::exit(2);
+            }
+        };
+        return;
+    }
+
+    let options = match parse_options(env::args().skip(1))
+    {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("{}", error);
+            eprintln!("Usage: blackjack [--seed <n>] [--seats <1-{}>] [--bots <n>] [--coach] [--show-count] [--count-system <name>]", table::MAX_SEATS);
+            process::exit(2);
+        }
+    };
+    let seed = options.seed.unwrap_or_else(|| rand::thread_rng().gen());
+    let mut table = table::Table::with_seed(rules::TableRules::default(), seed);
+    if let Some(system) = options.count_system.as_deref().and_then(counting_system)
+    {
+        table.set_counting_system(system);
+    }
+    let mut players: Vec<player::HumanPlayer> = PLAYER_NAMES[..options.seats].iter().map(|name| player::HumanPlayer::new(name)).collect();
+    for player in players.iter_mut()
+    {
+        player.set_coach(options.coach);
+    }
+    let mut bots: Vec<strategy::BasicStrategy> = BOT_NAMES[..options.bots].iter().map(|name| strategy::BasicStrategy::new(name)).collect();
+    println!("Seed: {} (replay this session with --seed {})", seed, seed);
+    println!("Table rules: {}", table.rules());
+    println!("Table limits: {} - {} chips", table.rules().min_bet, table.rules().max_bet);
+    let mut play_blackjack = ask_play_blackjack(true);
+
+    while play_blackjack
+    {
+        players.retain(|player| {
+            let broke = player.bankroll().is_broke(table.rules());
+            if broke
             {
-                if dealer.blackjack_hand()
+                println!("{} has {} left, which is below the table minimum. Thanks for playing!", player.name(), player.bankroll());
+                print_coach_summary(player);
+            }
+            !broke
+        });
+        if players.is_empty()
+        {
+            break;
+        }
+        for player in players.iter_mut()
+        {
+            player.ask_bet(table.rules());
+        }
+        if table.shoe().needs_shuffle()
+        {
+            println!("Shuffling the shoe");
+        }
+        println!("Dealing cards");
+        let mut seats: Vec<&mut dyn BlackjackPlayer> = players.iter_mut().map(|player| player as &mut dyn BlackjackPlayer)
+            .chain(bots.iter_mut().map(|bot| bot as &mut dyn BlackjackPlayer))
+            .collect();
+        match table.play_seats(&mut seats)
+        {
+            Ok(results) => {
+                for (player, result) in players.iter_mut().zip(results.iter())
+                {
+                    print_round_result(player, table.dealer(), result);
+                    let winnings = player.settle_round(result);
+                    print_winnings(player, winnings);
+                }
+                for (bot, result) in bots.iter().zip(results.iter().skip(players.len()))
+                {
+                    print_round_result(bot, table.dealer(), result);
+                }
+                if let Some(result) = results.first()
                 {
-                    println!("{0} got BLACKJACK!!! {0} WINS!!!???", dealer.name());
-                    dealer_plays = false;
+                    println!("{}'s hand: ", table.dealer().name());
+                    println!("{}", result.dealer_hand);
                 }
-                else
+                if options.show_count
                 {
-                    let choice = dealer.hit_or_stay();
-                    let _ = match choice
+                    println!("{} running count: {:+}, true count: {:+.1}", table.counter().system().name(), table.counter().running_count(), table.true_count());
+                    if let (Some(aces_seen), Some(extra_aces)) = (table.counter().aces_seen(), table.counter().extra_aces(table.shoe().decks_remaining()))
                     {
-                        player::BlackjackAction::Hit => dealer.add_card_to_hand(deck.deal_card()),
-                        player::BlackjackAction::Stay => {
-                            println!("{} Total: {}", dealer.name(), dealer.get_point_value());
-                            dealer_plays = false;
-
-                            let mut winner: Option<&str> = None;
-                            if dealer.get_point_value() > player.get_point_value()
-                            {
-                                winner = Some(dealer.name());
-                            }
-                            else if dealer.get_point_value() < player.get_point_value()
-                            {
-                                winner = Some(player.name());
-                            }
-
-                            match winner
-                            {
-                                Some(x) => println!("{} WINS!!!", x),
-                                None => println!("PUSH!"),
-                            };
-                        }
-                    };
+                        println!("Aces seen: {}, extra aces left in the shoe: {:+.1}", aces_seen, extra_aces);
+                    }
                 }
             }
-            else
-            {
-                println!("{} Total: {}", dealer.name(), dealer.get_point_value());
-                println!("{} BUSTS! {} wins this round!", dealer.name(), player.name());
-                dealer_plays = false;
+            Err(error) => {
+                println!("The round could not be finished: {}", error);
+                break;
             }
-        }
-
-        println!("{}'s hand: ", player.name());
-        println!("{}", player.hand);
-        println!("{}'s hand: ", dealer.name());
-        println!("{}", dealer.hand);
-
-        deck.collect_played_cards(player.discard_hand());
-        deck.collect_played_cards(dealer.discard_hand());
-
-        // Conditionally print deck
+        };
 
         play_blackjack = ask_play_blackjack(false);
     }
+
+    for player in players.iter()
+    {
+        print_coach_summary(player);
+    }
 }
 
 #[cfg(test)]
@@ -209,7 +506,7 @@ mod tests
         let mut writer = BufWriter::new(Cursor::new(vec![0; 512]));
 
         let response = ask_play_blackjack_get_response(true, reader, &mut writer);
-        assert_eq!(response, true);
+        assert!(response);
         let (recovered_writer, _buffered_data) = writer.into_parts();
         let captured_output = String::from_utf8(recovered_writer.into_inner()).unwrap_or(String::from("buffer write failed!"));
         assert_ne!(captured_output.len(), 0);
@@ -222,7 +519,7 @@ mod tests
         let mut writer = BufWriter::new(Cursor::new(vec![0; 512]));
 
         let response = ask_play_blackjack_get_response(true, reader, &mut writer);
-        assert_eq!(response, false);
+        assert!(!response);
         let (recovered_writer, _buffered_data) = writer.into_parts();
         let captured_output = String::from_utf8(recovered_writer.into_inner()).unwrap_or(String::from("buffer write failed!"));
         assert_ne!(captured_output.len(), 0);
@@ -235,7 +532,7 @@ mod tests
         let mut writer = BufWriter::new(Cursor::new(vec![0; 512]));
 
         let response = ask_play_blackjack_get_response(true, reader, &mut writer);
-        assert_eq!(response, false);
+        assert!(!response);
         let (recovered_writer, _buffered_data) = writer.into_parts();
         let captured_output = String::from_utf8(recovered_writer.into_inner()).unwrap_or(String::from("buffer write failed!"));
         assert_ne!(captured_output.len(), 0);
@@ -250,7 +547,7 @@ mod tests
         let mut writer = BufWriter::new(Cursor::new(vec![0; 512]));
 
         let response = ask_play_blackjack_get_response(true, reader, &mut writer);
-        assert_eq!(response, true);
+        assert!(response);
         let (recovered_writer, _buffered_data) = writer.into_parts();
         let captured_output = String::from_utf8(recovered_writer.into_inner()).unwrap_or(String::from("buffer write failed!"));
         assert_ne!(captured_output.len(), 0);
@@ -265,7 +562,7 @@ mod tests
         let mut writer = BufWriter::new(Cursor::new(vec![0; 512]));
 
         let response = ask_play_blackjack_get_response(true, reader, &mut writer);
-        assert_eq!(response, false);
+        assert!(!response);
         let (recovered_writer, _buffered_data) = writer.into_parts();
         let captured_output = String::from_utf8(recovered_writer.into_inner()).unwrap_or(String::from("buffer write failed!"));
         assert_ne!(captured_output.len(), 0);
@@ -280,7 +577,7 @@ mod tests
         let mut writer = BufWriter::new(Cursor::new(vec![0; 512]));
 
         let response = ask_play_blackjack_get_response(true, reader, &mut writer);
-        assert_eq!(response, false);
+        assert!(!response);
         let (recovered_writer, _buffered_data) = writer.into_parts();
         let captured_output = String::from_utf8(recovered_writer.into_inner()).unwrap_or(String::from("buffer write failed!"));
         assert_ne!(captured_output.len(), 0);
@@ -299,7 +596,7 @@ mod tests
         let mut writer = BufWriter::new(Cursor::new(vec![0; 512]));
 
         let response = ask_play_blackjack_get_response(false, reader, &mut writer);
-        assert_eq!(response, true);
+        assert!(response);
         let (recovered_writer, _buffered_data) = writer.into_parts();
         let captured_output = String::from_utf8(recovered_writer.into_inner()).unwrap_or(String::from("buffer write failed!"));
         assert_ne!(captured_output.len(), 0);
@@ -312,7 +609,7 @@ mod tests
         let mut writer = BufWriter::new(Cursor::new(vec![0; 512]));
 
         let response = ask_play_blackjack_get_response(false, reader, &mut writer);
-        assert_eq!(response, false);
+        assert!(!response);
         let (recovered_writer, _buffered_data) = writer.into_parts();
         let captured_output = String::from_utf8(recovered_writer.into_inner()).unwrap_or(String::from("buffer write failed!"));
         assert_ne!(captured_output.len(), 0);
@@ -325,7 +622,7 @@ mod tests
         let mut writer = BufWriter::new(Cursor::new(vec![0; 512]));
 
         let response = ask_play_blackjack_get_response(false, reader, &mut writer);
-        assert_eq!(response, false);
+        assert!(!response);
         let (recovered_writer, _buffered_data) = writer.into_parts();
         let captured_output = String::from_utf8(recovered_writer.into_inner()).unwrap_or(String::from("buffer write failed!"));
         assert_ne!(captured_output.len(), 0);
@@ -340,7 +637,7 @@ mod tests
         let mut writer = BufWriter::new(Cursor::new(vec![0; 512]));
 
         let response = ask_play_blackjack_get_response(false, reader, &mut writer);
-        assert_eq!(response, true);
+        assert!(response);
         let (recovered_writer, _buffered_data) = writer.into_parts();
         let captured_output = String::from_utf8(recovered_writer.into_inner()).unwrap_or(String::from("buffer write failed!"));
         assert_ne!(captured_output.len(), 0);
@@ -355,7 +652,7 @@ mod tests
         let mut writer = BufWriter::new(Cursor::new(vec![0; 512]));
 
         let response = ask_play_blackjack_get_response(false, reader, &mut writer);
-        assert_eq!(response, false);
+        assert!(!response);
         let (recovered_writer, _buffered_data) = writer.into_parts();
         let captured_output = String::from_utf8(recovered_writer.into_inner()).unwrap_or(String::from("buffer write failed!"));
         assert_ne!(captured_output.len(), 0);
@@ -370,7 +667,7 @@ mod tests
         let mut writer = BufWriter::new(Cursor::new(vec![0; 512]));
 
         let response = ask_play_blackjack_get_response(false, reader, &mut writer);
-        assert_eq!(response, false);
+        assert!(!response);
         let (recovered_writer, _buffered_data) = writer.into_parts();
         let captured_output = String::from_utf8(recovered_writer.into_inner()).unwrap_or(String::from("buffer write failed!"));
         assert_ne!(captured_output.len(), 0);
@@ -379,4 +676,125 @@ mod tests
         assert!(captured_output.contains("There seems to be a failure to communicate between us. Perhaps we'll play another time."));
         assert_eq!(captured_output.matches("I didn't understand that.").collect::<Vec<_>>().len(), 3);
     }
+
+    #[test]
+    fn test_parse_options()
+    {
+        let args = |values: &[&str]| values.iter().map(|value| value.to_string()).collect::<Vec<String>>().into_iter();
+
+        assert_eq!(parse_options(args(&[])), Ok(Options { seed: None, seats: 1, bots: 0, coach: false, show_count: false, count_system: None }));
+        assert_eq!(parse_options(args(&["--seed", "42"])).unwrap().seed, Some(42));
+        assert_eq!(parse_options(args(&["--seed=7"])).unwrap().seed, Some(7));
+        assert!(parse_options(args(&["--seed"])).is_err());
+        assert!(parse_options(args(&["--seed", "-1"])).is_err());
+        assert!(parse_options(args(&["--seed", "lucky"])).is_err());
+        assert!(parse_options(args(&["--show-count"])).unwrap().show_count);
+        assert!(parse_options(args(&["--show-count=yes"])).is_err());
+        assert_eq!(parse_options(args(&["--count-system", "zen"])).unwrap().count_system, Some(String::from("zen")));
+        assert!(parse_options(args(&["--count-system=KO"])).unwrap().show_count);
+        assert!(parse_options(args(&["--count-system", "2=1 3=1 T=-1"])).is_ok());
+        assert!(parse_options(args(&["--count-system", "uston"])).is_err());
+        assert!(parse_options(args(&["--count-system"])).is_err());
+        assert!(parse_options(args(&["--decks", "6"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_options_seats()
+    {
+        let args = |values: &[&str]| values.iter().map(|value| value.to_string()).collect::<Vec<String>>().into_iter();
+
+        assert_eq!(parse_options(args(&["--seats", "3", "--seed", "5"])), Ok(Options { seed: Some(5), seats: 3, bots: 0, coach: false, show_count: false, count_system: None }));
+        assert_eq!(parse_options(args(&["--seats=7"])).unwrap().seats, 7);
+        assert!(parse_options(args(&["--seats", "0"])).is_err());
+        assert!(parse_options(args(&["--seats", "8"])).is_err());
+        assert!(parse_options(args(&["--seats"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_options_bots()
+    {
+        let args = |values: &[&str]| values.iter().map(|value| value.to_string()).collect::<Vec<String>>().into_iter();
+
+        assert_eq!(parse_options(args(&["--bots", "2"])), Ok(Options { seed: None, seats: 1, bots: 2, coach: false, show_count: false, count_system: None }));
+        assert_eq!(parse_options(args(&["--seats=3", "--bots=4"])).unwrap().bots, 4);
+        assert!(parse_options(args(&["--seats=3", "--bots=5"])).is_err());
+        assert!(parse_options(args(&["--bots", "many"])).is_err());
+        assert!(parse_options(args(&["--coach"])).unwrap().coach);
+        assert!(parse_options(args(&["--coach=yes"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_ev_options()
+    {
+        let args = |values: &[&str]| values.iter().map(|value| value.to_string()).collect::<Vec<String>>().into_iter();
+
+        let options = parse_ev_options(args(&["10H", "6S", "vs", "9C", "--decks", "6", "--h17"])).unwrap();
+        assert_eq!(options.player, vec!["TH".parse().unwrap(), "6S".parse().unwrap()]);
+        assert_eq!(options.dealer, "9C".parse().unwrap());
+        assert_eq!(options.rules.decks, 6);
+        assert!(options.rules.dealer_hits_soft_17);
+
+        let options = parse_ev_options(args(&["--decks=2", "AS", "7D", "2C", "vs", "TH", "--rules", "european"])).unwrap();
+        assert_eq!(options.player.len(), 3);
+        assert_eq!(options.rules.decks, 2);
+        assert!(!options.rules.dealer_peeks);
+        assert!(!parse_ev_options(args(&["9S", "7D", "vs", "AH", "--s17", "--rules", "downtown"])).unwrap().rules.dealer_hits_soft_17);
+
+        assert!(parse_ev_options(args(&["10H", "vs", "9C"])).is_err());
+        assert!(parse_ev_options(args(&["10H", "6S", "9C"])).is_err());
+        assert!(parse_ev_options(args(&["10H", "6S", "vs"])).is_err());
+        assert!(parse_ev_options(args(&["10H", "6X", "vs", "9C"])).is_err());
+        assert!(parse_ev_options(args(&["10H", "6S", "vs", "9C", "8D"])).is_err());
+        assert!(parse_ev_options(args(&["10H", "6S", "vs", "9C", "--decks", "0"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_edge_options()
+    {
+        let args = |values: &[&str]| values.iter().map(|value| value.to_string()).collect::<Vec<String>>().into_iter();
+
+        assert_eq!(parse_edge_options(args(&[])), Ok(EdgeOptions { rules: rules::TableRules::default(), compare: false }));
+        let options = parse_edge_options(args(&["--payout", "6:5", "--h17", "--rules", "downtown", "--decks=1"])).unwrap();
+        assert_eq!(options.rules.blackjack_payout, rules::BlackjackPayout::SixToFive);
+        assert!(options.rules.dealer_hits_soft_17);
+        assert_eq!(options.rules.decks, 1);
+        assert!(parse_edge_options(args(&["--compare"])).unwrap().compare);
+        assert!(parse_edge_options(args(&["--payout", "2:1"])).is_err());
+        assert!(parse_edge_options(args(&["--compare=yes"])).is_err());
+        assert!(parse_edge_options(args(&["--rules", "macau"])).is_err());
+    }
+
+    #[test]
+    fn test_print_insurance_result()
+    {
+        let dealer: player::Dealer = BlackjackPlayer::new("Dealer");
+        let hand_of = |cards: &str| {
+            let mut hand = hand::Hand::new();
+            for card in cards.split_whitespace()
+            {
+                hand.add_card_to_hand(card.parse().unwrap());
+            }
+            hand
+        };
+        let capture = |result: &table::RoundResult| {
+            let mut writer = BufWriter::new(Cursor::new(vec![]));
+            print_insurance_result(&dealer, result, &mut writer);
+            let (recovered_writer, _buffered_data) = writer.into_parts();
+            String::from_utf8(recovered_writer.into_inner()).unwrap_or(String::from("buffer write failed!"))
+        };
+
+        let mut result = table::RoundResult {
+            hands: vec![table::HandResult { hand: hand_of("AS KD"), outcome: outcome::Outcome::Push, net: 0.0 }],
+            dealer_hand: hand_of("AH QC"),
+            insurance: Some(1.0),
+        };
+        assert_eq!(capture(&result), "Even money paid.\n");
+
+        result.hands[0] = table::HandResult { hand: hand_of("TS 9D"), outcome: outcome::Outcome::DealerBlackjack, net: -1.0 };
+        assert_eq!(capture(&result), "Dealer has BLACKJACK! Insurance pays 2:1.\n");
+
+        result.insurance = Some(-0.5);
+        result.dealer_hand = hand_of("AH 7C");
+        assert_eq!(capture(&result), "Dealer doesn't have Blackjack. Insurance lost.\n");
+    }
 }
@@ -0,0 +1,249 @@
+use std::fmt;
+
+use crate::hand;
+use crate::rules;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Outcome
+{
+    PlayerBlackjack,
+    PlayerWin,
+    DealerBust,
+    DealerBlackjack,
+    DealerWin,
+    PlayerBust,
+    Push,
+    Surrender,
+}
+
+impl fmt::Display for Outcome
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match &self
+        {
+            Outcome::PlayerBlackjack => write!(f, "Player Blackjack"),
+            Outcome::PlayerWin => write!(f, "Player Wins"),
+            Outcome::DealerBust => write!(f, "Dealer Busts"),
+            Outcome::DealerBlackjack => write!(f, "Dealer Blackjack"),
+            Outcome::DealerWin => write!(f, "Dealer Wins"),
+            Outcome::PlayerBust => write!(f, "Player Busts"),
+            Outcome::Push => write!(f, "Push"),
+            Outcome::Surrender => write!(f, "Surrender"),
+        }
+    }
+}
+
+impl Outcome
+{
+    pub fn player_wins(&self) -> bool
+    {
+        matches!(self, Outcome::PlayerBlackjack | Outcome::PlayerWin | Outcome::DealerBust)
+    }
+
+    pub fn dealer_wins(&self) -> bool
+    {
+        matches!(self, Outcome::DealerBlackjack | Outcome::DealerWin | Outcome::PlayerBust | Outcome::Surrender)
+    }
+
+    pub fn payout(&self, rules: &rules::TableRules) -> f64
+    {
+        match &self
+        {
+            Outcome::PlayerBlackjack => rules.blackjack_payout.ratio(),
+            Outcome::PlayerWin | Outcome::DealerBust => 1.0,
+            Outcome::Push => 0.0,
+            Outcome::Surrender => -0.5,
+            Outcome::DealerBlackjack | Outcome::DealerWin | Outcome::PlayerBust => -1.0,
+        }
+    }
+}
+
+pub fn settle(player: &hand::Hand, dealer: &hand::Hand) -> Outcome
+{
+    if player.is_surrendered()
+    {
+        return Outcome::Surrender;
+    }
+    if player.value().is_blackjack()
+    {
+        return match dealer.value().is_blackjack()
+        {
+            true => Outcome::Push,
+            false => Outcome::PlayerBlackjack,
+        };
+    }
+    if dealer.value().is_blackjack()
+    {
+        return Outcome::DealerBlackjack;
+    }
+    if player.value().is_bust()
+    {
+        return Outcome::PlayerBust;
+    }
+    if dealer.value().is_bust()
+    {
+        return Outcome::DealerBust;
+    }
+
+    match player.get_point_value().cmp(&dealer.get_point_value())
+    {
+        std::cmp::Ordering::Greater => Outcome::PlayerWin,
+        std::cmp::Ordering::Less => Outcome::DealerWin,
+        std::cmp::Ordering::Equal => Outcome::Push,
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::card;
+    use crate::rank;
+    use crate::suit;
+
+    fn hand_of(ranks: &[rank::Rank]) -> hand::Hand
+    {
+        let mut hand = hand::Hand::new();
+        for rank in ranks
+        {
+            hand.add_card_to_hand(card::Card { rank: *rank, suit: suit::Suit::Spades });
+        }
+        hand
+    }
+
+    #[test]
+    fn test_format_outcome()
+    {
+        assert_eq!(format!("{}", Outcome::PlayerBlackjack), "Player Blackjack");
+        assert_eq!(format!("{}", Outcome::PlayerWin), "Player Wins");
+        assert_eq!(format!("{}", Outcome::DealerBust), "Dealer Busts");
+        assert_eq!(format!("{}", Outcome::DealerBlackjack), "Dealer Blackjack");
+        assert_eq!(format!("{}", Outcome::DealerWin), "Dealer Wins");
+        assert_eq!(format!("{}", Outcome::PlayerBust), "Player Busts");
+        assert_eq!(format!("{}", Outcome::Push), "Push");
+        assert_eq!(format!("{}", Outcome::Surrender), "Surrender");
+    }
+
+    #[test]
+    fn test_settle_player_blackjack()
+    {
+        let player = hand_of(&[rank::Rank::Ace, rank::Rank::King]);
+        assert_eq!(settle(&player, &hand_of(&[rank::Rank::Ten, rank::Rank::Nine])), Outcome::PlayerBlackjack);
+        assert_eq!(settle(&player, &hand_of(&[rank::Rank::Ten, rank::Rank::Five, rank::Rank::Six])), Outcome::PlayerBlackjack);
+    }
+
+    #[test]
+    fn test_settle_both_blackjack_is_push()
+    {
+        let player = hand_of(&[rank::Rank::Ace, rank::Rank::King]);
+        let dealer = hand_of(&[rank::Rank::Queen, rank::Rank::Ace]);
+        assert_eq!(settle(&player, &dealer), Outcome::Push);
+    }
+
+    #[test]
+    fn test_settle_dealer_blackjack()
+    {
+        let dealer = hand_of(&[rank::Rank::Ace, rank::Rank::Jack]);
+        assert_eq!(settle(&hand_of(&[rank::Rank::Ten, rank::Rank::Nine]), &dealer), Outcome::DealerBlackjack);
+        assert_eq!(settle(&hand_of(&[rank::Rank::Seven, rank::Rank::Four, rank::Rank::Queen]), &dealer), Outcome::DealerBlackjack);
+    }
+
+    #[test]
+    fn test_settle_player_bust()
+    {
+        let player = hand_of(&[rank::Rank::Ten, rank::Rank::Six, rank::Rank::Eight]);
+        assert_eq!(settle(&player, &hand_of(&[rank::Rank::Ten, rank::Rank::Seven])), Outcome::PlayerBust);
+        assert_eq!(settle(&player, &hand_of(&[rank::Rank::Ten, rank::Rank::Six, rank::Rank::Nine])), Outcome::PlayerBust);
+    }
+
+    #[test]
+    fn test_settle_dealer_bust()
+    {
+        let dealer = hand_of(&[rank::Rank::Ten, rank::Rank::Six, rank::Rank::Nine]);
+        assert_eq!(settle(&hand_of(&[rank::Rank::Ten, rank::Rank::Two]), &dealer), Outcome::DealerBust);
+    }
+
+    #[test]
+    fn test_settle_player_win()
+    {
+        let player = hand_of(&[rank::Rank::Ten, rank::Rank::Five, rank::Rank::Five]);
+        assert_eq!(settle(&player, &hand_of(&[rank::Rank::Ten, rank::Rank::Nine])), Outcome::PlayerWin);
+        let player = hand_of(&[rank::Rank::Seven, rank::Rank::Four, rank::Rank::Queen]);
+        assert_eq!(settle(&player, &hand_of(&[rank::Rank::Ten, rank::Rank::King])), Outcome::PlayerWin);
+    }
+
+    #[test]
+    fn test_settle_dealer_win()
+    {
+        let player = hand_of(&[rank::Rank::Ten, rank::Rank::Six]);
+        assert_eq!(settle(&player, &hand_of(&[rank::Rank::Ten, rank::Rank::Seven])), Outcome::DealerWin);
+        let dealer = hand_of(&[rank::Rank::Six, rank::Rank::Five, rank::Rank::King]);
+        assert_eq!(settle(&hand_of(&[rank::Rank::Ten, rank::Rank::King]), &dealer), Outcome::DealerWin);
+    }
+
+    #[test]
+    fn test_settle_push()
+    {
+        let player = hand_of(&[rank::Rank::Ten, rank::Rank::Eight]);
+        assert_eq!(settle(&player, &hand_of(&[rank::Rank::Nine, rank::Rank::Nine])), Outcome::Push);
+        let player = hand_of(&[rank::Rank::Ten, rank::Rank::Five, rank::Rank::Six]);
+        assert_eq!(settle(&player, &hand_of(&[rank::Rank::Ten, rank::Rank::Four, rank::Rank::Seven])), Outcome::Push);
+    }
+
+    #[test]
+    fn test_outcome_winners()
+    {
+        assert!(Outcome::PlayerBlackjack.player_wins());
+        assert!(Outcome::PlayerWin.player_wins());
+        assert!(Outcome::DealerBust.player_wins());
+        assert!(Outcome::DealerBlackjack.dealer_wins());
+        assert!(Outcome::DealerWin.dealer_wins());
+        assert!(Outcome::PlayerBust.dealer_wins());
+        assert!(Outcome::Surrender.dealer_wins());
+        assert!(!Outcome::Push.player_wins());
+        assert!(!Outcome::Push.dealer_wins());
+    }
+
+    #[test]
+    fn test_settle_split_twenty_one_is_not_blackjack()
+    {
+        let mut player = hand_of(&[rank::Rank::Ace, rank::Rank::Ace]);
+        let mut second_hand = player.split();
+        player.add_card_to_hand(card::Card { rank: rank::Rank::King, suit: suit::Suit::Hearts });
+        second_hand.add_card_to_hand(card::Card { rank: rank::Rank::Nine, suit: suit::Suit::Hearts });
+
+        assert_eq!(settle(&player, &hand_of(&[rank::Rank::Ten, rank::Rank::Nine])), Outcome::PlayerWin);
+        assert_eq!(settle(&player, &hand_of(&[rank::Rank::Ten, rank::Rank::Five, rank::Rank::Six])), Outcome::Push);
+        assert_eq!(settle(&second_hand, &hand_of(&[rank::Rank::Ten, rank::Rank::Nine])), Outcome::PlayerWin);
+    }
+
+    #[test]
+    fn test_settle_surrender()
+    {
+        let mut player = hand_of(&[rank::Rank::Ten, rank::Rank::Six]);
+        player.surrender();
+
+        assert_eq!(settle(&player, &hand_of(&[rank::Rank::Ten, rank::Rank::Nine])), Outcome::Surrender);
+        assert_eq!(settle(&player, &hand_of(&[rank::Rank::Ace, rank::Rank::King])), Outcome::Surrender);
+        assert_eq!(settle(&player, &hand_of(&[rank::Rank::Ten, rank::Rank::Six, rank::Rank::Nine])), Outcome::Surrender);
+    }
+
+    #[test]
+    fn test_outcome_payout()
+    {
+        let rules = rules::TableRules::default();
+        let six_to_five = rules::TableRules { blackjack_payout: rules::BlackjackPayout::SixToFive, ..rules::TableRules::default() };
+
+        assert_eq!(Outcome::PlayerBlackjack.payout(&rules), 1.5);
+        assert_eq!(Outcome::PlayerBlackjack.payout(&six_to_five), 1.2);
+        assert_eq!(Outcome::PlayerWin.payout(&six_to_five), 1.0);
+        assert_eq!(Outcome::PlayerWin.payout(&rules), 1.0);
+        assert_eq!(Outcome::DealerBust.payout(&rules), 1.0);
+        assert_eq!(Outcome::Push.payout(&rules), 0.0);
+        assert_eq!(Outcome::Surrender.payout(&rules), -0.5);
+        assert_eq!(Outcome::DealerBlackjack.payout(&rules), -1.0);
+        assert_eq!(Outcome::DealerWin.payout(&rules), -1.0);
+        assert_eq!(Outcome::PlayerBust.payout(&rules), -1.0);
+    }
+}
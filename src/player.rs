@@ -1,25 +1,91 @@
+use std::fmt;
+use std::cell;
 use std::io;
 use std::io::Write;
 use std::io::BufRead;
 use crate::hand;
 use crate::card;
+use crate::rules;
+use crate::table;
+use crate::bankroll;
+use crate::strategy;
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum BlackjackAction
 {
     Hit,
     Stay,
+    Double,
+    Split,
+    Surrender,
+}
+
+impl fmt::Display for BlackjackAction
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match &self
+        {
+            BlackjackAction::Hit => write!(f, "Hit"),
+            BlackjackAction::Stay => write!(f, "Stay"),
+            BlackjackAction::Double => write!(f, "Double"),
+            BlackjackAction::Split => write!(f, "Split"),
+            BlackjackAction::Surrender => write!(f, "Surrender"),
+        }
+    }
 }
 
 pub trait BlackjackPlayer
 {
-    fn new(name: &'static str) -> Self;
+    fn new(name: &'static str) -> Self where Self: Sized;
     fn name(&self) -> &'static str;
-    fn hit_or_stay(&self) -> BlackjackAction;
-    fn add_card_to_hand(&mut self, card: card::Card);
-    fn discard_hand(&mut self) -> Vec<card::Card>;
-    fn get_point_value(&self) -> u32;
-    fn get_num_cards(&self) -> u32;
+    fn hit_or_stay(&self, hand_index: usize, actions: &[BlackjackAction], view: &table::TableView) -> BlackjackAction;
+    fn hands(&self) -> &[hand::Hand];
+    fn hands_mut(&mut self) -> &mut Vec<hand::Hand>;
+
+    fn take_insurance(&self, _even_money: bool, _view: &table::TableView) -> bool
+    {
+        false
+    }
+
+    fn hand(&self) -> &hand::Hand
+    {
+        &self.hands()[0]
+    }
+
+    fn add_card_to_hand(&mut self, card: card::Card)
+    {
+        self.hands_mut()[0].add_card_to_hand(card);
+    }
+
+    fn split_hand(&mut self, hand_index: usize)
+    {
+        let hands = self.hands_mut();
+        let new_hand = hands[hand_index].split();
+        hands.insert(hand_index + 1, new_hand);
+    }
+
+    fn discard_hand(&mut self) -> Vec<card::Card>
+    {
+        let hands = self.hands_mut();
+        let mut discard: Vec<card::Card> = vec![];
+        for hand in hands.iter_mut()
+        {
+            discard.append(&mut hand.discard_hand());
+        }
+        hands.truncate(1);
+        discard
+    }
+
+    fn get_point_value(&self) -> u32
+    {
+        self.hand().get_point_value()
+    }
+
+    fn get_num_cards(&self) -> u32
+    {
+        self.hand().get_num_cards()
+    }
 
     fn hand_under_21(&self) -> bool
     {
@@ -28,52 +94,228 @@ pub trait BlackjackPlayer
 
     fn blackjack_hand(&self) -> bool
     {
-        self.get_point_value() == 21 && self.get_num_cards() == 2
+        self.hand().value().is_blackjack()
+    }
+}
+
+fn action_prompt(actions: &[BlackjackAction]) -> String
+{
+    let mut prompt = String::new();
+    for (index, action) in actions.iter().enumerate()
+    {
+        if index != 0
+        {
+            prompt.push_str(if index == actions.len() - 1 { " or " } else { ", " });
+        }
+        prompt.push_str(&action.to_string());
     }
+    prompt.push_str("? ");
+    prompt
 }
 
 pub struct HumanPlayer
 {
     name: &'static str,
-    pub hand: hand::Hand
+    hands: Vec<hand::Hand>,
+    bankroll: bankroll::Bankroll,
+    bet: u32,
+    coach: bool,
+    coached_decisions: cell::Cell<u32>,
+    correct_decisions: cell::Cell<u32>,
 }
 
 impl HumanPlayer
 {
-    fn hit_or_stay_strategy<R: io::Read, W: io::Write>(&self, mut reader: io::BufReader<R>, writer: &mut io::BufWriter<W>) -> BlackjackAction
+    pub fn with_bankroll(name: &'static str, chips: u32) -> HumanPlayer
+    {
+        HumanPlayer { bankroll: bankroll::Bankroll::new(chips), ..BlackjackPlayer::new(name) }
+    }
+
+    pub fn set_coach(&mut self, coach: bool)
+    {
+        self.coach = coach;
+    }
+
+    pub fn coached_decisions(&self) -> u32
+    {
+        self.coached_decisions.get()
+    }
+
+    pub fn coach_accuracy(&self) -> Option<f64>
+    {
+        match self.coached_decisions.get()
+        {
+            0 => None,
+            decisions => Some(100.0 * self.correct_decisions.get() as f64 / decisions as f64),
+        }
+    }
+
+    pub fn bankroll(&self) -> &bankroll::Bankroll
+    {
+        &self.bankroll
+    }
+
+    pub fn bet(&self) -> u32
+    {
+        self.bet
+    }
+
+    pub fn ask_bet(&mut self, rules: &rules::TableRules) -> u32
+    {
+        self.ask_bet_strategy(rules, io::BufReader::new(io::stdin()), &mut io::BufWriter::new(io::stdout()))
+    }
+
+    pub fn settle_round(&mut self, result: &table::RoundResult) -> i64
+    {
+        self.bankroll.settle(self.bet, result)
+    }
+
+    fn uncommitted_chips(&self) -> u32
+    {
+        let wagers: u32 = self.hands.iter().map(|hand| if hand.is_doubled() { 2 } else { 1 }).sum();
+        self.bankroll.balance().saturating_sub(self.bet * wagers)
+    }
+
+    fn affordable_actions(&self, actions: &[BlackjackAction]) -> Vec<BlackjackAction>
+    {
+        let can_raise = self.uncommitted_chips() >= self.bet;
+        actions.iter().copied().filter(|action| can_raise || !matches!(action, BlackjackAction::Double | BlackjackAction::Split)).collect()
+    }
+
+    fn coach_decision<W: io::Write>(&self, hand_index: usize, actions: &[BlackjackAction], view: &table::TableView, action: BlackjackAction, writer: &mut io::BufWriter<W>)
+    {
+        let up_card = match (self.coach, view.dealer_up_card)
+        {
+            (true, Some(up_card)) => up_card,
+            _ => return,
+        };
+        let hand = &self.hands[hand_index];
+        let correct_action = strategy::recommend(hand, up_card, &view.rules, actions);
+
+        self.coached_decisions.set(self.coached_decisions.get() + 1);
+        if action == correct_action
+        {
+            self.correct_decisions.set(self.correct_decisions.get() + 1);
+        }
+        else
+        {
+            let _ = writeln!(writer, "Coach: {}", strategy::explain(hand, up_card, correct_action));
+        }
+    }
+
+    fn ask_bet_strategy<R: io::Read, W: io::Write>(&mut self, rules: &rules::TableRules, mut reader: io::BufReader<R>, writer: &mut io::BufWriter<W>) -> u32
+    {
+        let mut tries_remaining = 3u8;
+        let mut bet: Option<u32> = None;
+        let mut input = String::new();
+        let default_bet = match self.bankroll.check_bet(self.bet, rules)
+        {
+            Ok(()) => self.bet,
+            Err(_) => rules.min_bet,
+        };
+        let prompt = format!("Place your bet ({}-{}, Enter for {}): ", rules.min_bet, rules.max_bet, default_bet);
+
+        let _ = writeln!(writer, "{} has {}.", self.name, self.bankroll);
+        let _ = write!(writer, "{}", prompt);
+        writer.flush().unwrap();
+        while bet.is_none() && tries_remaining > 0
+        {
+            input.clear();
+            let _ = reader.read_line(&mut input);
+            let amount = match input.trim()
+            {
+                "" => Some(default_bet),
+                amount => amount.parse::<u32>().ok(),
+            };
+            tries_remaining -= 1;
+
+            let _ = match amount.map(|amount| (amount, self.bankroll.check_bet(amount, rules)))
+            {
+                Some((amount, Ok(()))) => {
+                    bet = Some(amount);
+                    writeln!(writer, "Okay, you bet {} chips.", amount)
+                }
+                Some((amount, Err(error))) => writeln!(writer, "You can't bet {} chips: {}.", amount, error),
+                None => writeln!(writer, "That didn't make any sense..."),
+            };
+
+            if bet.is_none() && tries_remaining > 0
+            {
+                let _ = write!(writer, "Let's try again... {}", prompt);
+            }
+            writer.flush().unwrap();
+        }
+
+        let fallback = || {
+            let _ = writeln!(writer, "Let's just assume you want to bet {} chips.", default_bet);
+            writer.flush().unwrap();
+            Some(default_bet)
+        };
+
+        let bet = bet.or_else(fallback).unwrap();
+        self.bet = bet;
+        bet
+    }
+
+    fn hit_or_stay_strategy<R: io::Read, W: io::Write>(&self, hand_index: usize, actions: &[BlackjackAction], view: &table::TableView, mut reader: io::BufReader<R>, writer: &mut io::BufWriter<W>) -> BlackjackAction
     {
         let mut tries_remaining = 3u8;
         let mut action: Option<BlackjackAction> = None;
         let mut input = String::new();
+        let prompt = action_prompt(actions);
 
-        let _ = write!(writer, "Hit or Stay? ");
+        if let Some(up_card) = view.dealer_up_card
+        {
+            let _ = writeln!(writer, "Dealer shows: {}", up_card);
+        }
+        let _ = match self.hands.len()
+        {
+            1 => writeln!(writer, "{}'s hand: ", self.name),
+            n => writeln!(writer, "{}'s hand {} of {}: ", self.name, hand_index + 1, n),
+        };
+        let _ = writeln!(writer, "{} ({})", self.hands[hand_index], self.hands[hand_index].value());
+        let _ = write!(writer, "{}", prompt);
         writer.flush().unwrap();
-        while action == None && tries_remaining > 0
+        while action.is_none() && tries_remaining > 0
         {
             input.clear();
             let _ = reader.read_line(&mut input);
-            action = match input.to_lowercase().as_str().trim()
+            let choice = match input.to_lowercase().as_str().trim()
             {
                 "hit" => Some(BlackjackAction::Hit),
                 "stay" => Some(BlackjackAction::Stay),
+                "double" | "d" => Some(BlackjackAction::Double),
+                "split" => Some(BlackjackAction::Split),
+                "surrender" => Some(BlackjackAction::Surrender),
                 _ => None
             };
+            action = choice.filter(|choice| actions.contains(choice));
             tries_remaining -= 1;
 
-            let _ = match action
+            let _ = match (action, choice)
             {
-                Some(BlackjackAction::Hit) => writeln!(writer, "Okay, you want to hit."),
-                Some(BlackjackAction::Stay) => writeln!(writer, "Okay, you want to stay."),
-                _ => writeln!(writer, "That didn't make any sense..."),
+                (Some(BlackjackAction::Hit), _) => writeln!(writer, "Okay, you want to hit."),
+                (Some(BlackjackAction::Stay), _) => writeln!(writer, "Okay, you want to stay."),
+                (Some(BlackjackAction::Double), _) => writeln!(writer, "Okay, you want to double down."),
+                (Some(BlackjackAction::Split), _) => writeln!(writer, "Okay, you want to split."),
+                (Some(BlackjackAction::Surrender), _) => writeln!(writer, "Okay, you want to surrender."),
+                (None, Some(refused)) => writeln!(writer, "You can't {} this hand.", refused.to_string().to_lowercase()),
+                (None, None) => writeln!(writer, "That didn't make any sense..."),
             };
 
             if action.is_none() && tries_remaining > 0
             {
-                let _ = write!(writer, "Let's try again... Hit or Stay? ");
+                let _ = write!(writer, "Let's try again... {}", prompt);
             }
             writer.flush().unwrap();
         }
 
+        if let Some(action) = action
+        {
+            self.coach_decision(hand_index, actions, view, action, writer);
+            writer.flush().unwrap();
+        }
+
         let fallback = || {
             let _ = writeln!(writer, "Let's just assume you want to stay.");
             writer.flush().unwrap();
@@ -82,13 +324,78 @@ impl HumanPlayer
 
         action.or_else(fallback).unwrap()
     }
+
+    fn take_insurance_strategy<R: io::Read, W: io::Write>(&self, even_money: bool, mut reader: io::BufReader<R>, writer: &mut io::BufWriter<W>) -> bool
+    {
+        let mut tries_remaining = 3u8;
+        let mut choice: Option<bool> = None;
+        let mut input = String::new();
+        let prompt = match even_money
+        {
+            true => "Dealer shows an Ace. Take even money? (yes/no) ",
+            false => "Dealer shows an Ace. Take insurance? (yes/no) ",
+        };
+
+        if self.uncommitted_chips() < bankroll::insurance_stake(self.bet)
+        {
+            let _ = writeln!(writer, "Dealer shows an Ace, but you don't have enough chips left for insurance.");
+            writer.flush().unwrap();
+            return false;
+        }
+
+        let _ = writeln!(writer, "{}'s hand: ", self.name);
+        let _ = writeln!(writer, "{}", self.hands[0]);
+        let _ = write!(writer, "{}", prompt);
+        writer.flush().unwrap();
+        while choice.is_none() && tries_remaining > 0
+        {
+            input.clear();
+            let _ = reader.read_line(&mut input);
+            choice = match input.to_lowercase().as_str().trim()
+            {
+                "yes" | "y" => Some(true),
+                "no" | "n" => Some(false),
+                _ => None
+            };
+            tries_remaining -= 1;
+
+            let _ = match choice
+            {
+                Some(true) => writeln!(writer, "Okay, you're insured."),
+                Some(false) => writeln!(writer, "Okay, no insurance."),
+                None => writeln!(writer, "That didn't make any sense..."),
+            };
+
+            if choice.is_none() && tries_remaining > 0
+            {
+                let _ = write!(writer, "Let's try again... {}", prompt);
+            }
+            writer.flush().unwrap();
+        }
+
+        let fallback = || {
+            let _ = writeln!(writer, "Let's just assume you don't want insurance.");
+            writer.flush().unwrap();
+            Some(false)
+        };
+
+        choice.or_else(fallback).unwrap()
+    }
 }
 
 impl BlackjackPlayer for HumanPlayer
 {
     fn new(name: &'static str) -> HumanPlayer
     {
-        HumanPlayer { name: name, hand: hand::Hand::new() }
+        HumanPlayer {
+            name,
+            hands: vec![hand::Hand::new()],
+            bankroll: bankroll::Bankroll::default(),
+            bet: 0,
+            coach: false,
+            coached_decisions: cell::Cell::new(0),
+            correct_decisions: cell::Cell::new(0),
+        }
     }
 
     fn name(&self) -> &'static str
@@ -96,54 +403,50 @@ impl BlackjackPlayer for HumanPlayer
         self.name
     }
 
-    fn hit_or_stay(&self) -> BlackjackAction
-    {
-        self.hit_or_stay_strategy(io::BufReader::new(io::stdin()), &mut io::BufWriter::new(io::stdout()))
-    }
-
-    fn add_card_to_hand(&mut self, card: card::Card)
+    fn hit_or_stay(&self, hand_index: usize, actions: &[BlackjackAction], view: &table::TableView) -> BlackjackAction
     {
-        self.hand.add_card_to_hand(card);
+        self.hit_or_stay_strategy(hand_index, &self.affordable_actions(actions), view, io::BufReader::new(io::stdin()), &mut io::BufWriter::new(io::stdout()))
     }
 
-    fn discard_hand(&mut self) -> Vec<card::Card>
+    fn take_insurance(&self, even_money: bool, _: &table::TableView) -> bool
     {
-        self.hand.discard_hand()
+        self.take_insurance_strategy(even_money, io::BufReader::new(io::stdin()), &mut io::BufWriter::new(io::stdout()))
     }
 
-    fn get_point_value(&self) -> u32
+    fn hands(&self) -> &[hand::Hand]
     {
-        self.hand.get_point_value()
+        &self.hands
     }
 
-    fn get_num_cards(&self) -> u32
+    fn hands_mut(&mut self) -> &mut Vec<hand::Hand>
     {
-        self.hand.get_num_cards()
+        &mut self.hands
     }
-
 }
 
 pub struct Dealer
 {
     name: &'static str,
-    pub hand: hand::Hand
+    hands: Vec<hand::Hand>,
+    hits_soft_17: bool,
 }
 
-impl BlackjackPlayer for Dealer
+impl Dealer
 {
-    fn new(_: &'static str) -> Dealer
+    pub fn with_rules(rules: &rules::TableRules) -> Dealer
     {
-        Dealer { name: "Dealer", hand: hand::Hand::new() }
+        Dealer { hits_soft_17: rules.dealer_hits_soft_17, ..Dealer::new("Dealer") }
     }
 
-    fn name(&self) -> &'static str
+    pub fn up_card(&self) -> Option<card::Card>
     {
-        self.name
+        self.hand().cards().first().copied()
     }
 
-    fn hit_or_stay(&self) -> BlackjackAction
+    pub fn next_action(&self) -> BlackjackAction
     {
-        if self.hand.get_point_value() >= 17
+        let value = self.hand().value();
+        if value.total() > 17 || (value.total() == 17 && !(self.hits_soft_17 && value.is_soft()))
         {
             BlackjackAction::Stay
         }
@@ -152,27 +455,34 @@ impl BlackjackPlayer for Dealer
             BlackjackAction::Hit
         }
     }
+}
 
-    fn add_card_to_hand(&mut self, card: card::Card)
+impl BlackjackPlayer for Dealer
+{
+    fn new(_: &'static str) -> Dealer
     {
-        self.hand.add_card_to_hand(card);
+        Dealer { name: "Dealer", hands: vec![hand::Hand::new()], hits_soft_17: false }
     }
 
-    fn discard_hand(&mut self) -> Vec<card::Card>
+    fn name(&self) -> &'static str
     {
-        self.hand.discard_hand()
+        self.name
     }
 
-    fn get_point_value(&self) -> u32
+    fn hit_or_stay(&self, _: usize, _: &[BlackjackAction], _: &table::TableView) -> BlackjackAction
     {
-        self.hand.get_point_value()
+        self.next_action()
     }
 
-    fn get_num_cards(&self) -> u32
+    fn hands(&self) -> &[hand::Hand]
     {
-        self.hand.get_num_cards()
+        &self.hands
     }
 
+    fn hands_mut(&mut self) -> &mut Vec<hand::Hand>
+    {
+        &mut self.hands
+    }
 }
 
 #[cfg(test)]
@@ -185,6 +495,13 @@ mod tests
     use std::io::BufReader;
     use std::io::BufWriter;
 
+    const HIT_OR_STAY: [BlackjackAction; 2] = [BlackjackAction::Hit, BlackjackAction::Stay];
+
+    fn no_view() -> table::TableView<'static>
+    {
+        table::TableView { rules: rules::TableRules::default(), dealer_up_card: None, hands: &[], other_seats: &[], cards_remaining: 312, decks_remaining: 6.0, running_count: 0.0, true_count: 0.0 }
+    }
+
     #[test]
     fn test_human_player_new_player_name()
     {
@@ -278,7 +595,7 @@ mod tests
         let mut writer = BufWriter::new(Cursor::new(vec![0; 512]));
 
         let player: HumanPlayer = BlackjackPlayer::new("Player");
-        assert_eq!(player.hit_or_stay_strategy(BufReader::new(cursor), &mut writer), BlackjackAction::Hit);
+        assert_eq!(player.hit_or_stay_strategy(0, &HIT_OR_STAY, &no_view(), BufReader::new(cursor), &mut writer), BlackjackAction::Hit);
         let (recovered_writer, _buffered_data) = writer.into_parts();
         let captured_output = String::from_utf8(recovered_writer.into_inner()).unwrap_or(String::from("buffer write failed!"));
         assert_ne!(captured_output.len(), 0);
@@ -287,7 +604,7 @@ mod tests
 
         let cursor = Cursor::new(String::from("Stay").into_bytes());
         let mut writer = BufWriter::new(Cursor::new(vec![0; 512]));
-        assert_eq!(player.hit_or_stay_strategy(BufReader::new(cursor), &mut writer), BlackjackAction::Stay);
+        assert_eq!(player.hit_or_stay_strategy(0, &HIT_OR_STAY, &no_view(), BufReader::new(cursor), &mut writer), BlackjackAction::Stay);
         let (recovered_writer, _buffered_data) = writer.into_parts();
         let captured_output = String::from_utf8(recovered_writer.into_inner()).unwrap_or(String::from("buffer write failed!"));
         assert_ne!(captured_output.len(), 0);
@@ -296,7 +613,7 @@ mod tests
 
         let cursor = Cursor::new(String::from("Anything Else").into_bytes());
         let mut writer = BufWriter::new(Cursor::new(vec![0; 512]));
-        assert_eq!(player.hit_or_stay_strategy(BufReader::new(cursor), &mut writer), BlackjackAction::Stay);
+        assert_eq!(player.hit_or_stay_strategy(0, &HIT_OR_STAY, &no_view(), BufReader::new(cursor), &mut writer), BlackjackAction::Stay);
         let (recovered_writer, _buffered_data) = writer.into_parts();
         let captured_output = String::from_utf8(recovered_writer.into_inner()).unwrap_or(String::from("buffer write failed!"));
         assert_ne!(captured_output.len(), 0);
@@ -307,7 +624,7 @@ mod tests
 
         let cursor = Cursor::new(String::from("asdf\njkl;\nHit\n").into_bytes());
         let mut writer = BufWriter::new(Cursor::new(vec![0; 512]));
-        assert_eq!(player.hit_or_stay_strategy(BufReader::new(cursor), &mut writer), BlackjackAction::Hit);
+        assert_eq!(player.hit_or_stay_strategy(0, &HIT_OR_STAY, &no_view(), BufReader::new(cursor), &mut writer), BlackjackAction::Hit);
         let (recovered_writer, _buffered_data) = writer.into_parts();
         let captured_output = String::from_utf8(recovered_writer.into_inner()).unwrap_or(String::from("buffer write failed!"));
         assert_ne!(captured_output.len(), 0);
@@ -318,7 +635,7 @@ mod tests
 
         let cursor = Cursor::new(String::from("asdf\njkl;\nStay\n").into_bytes());
         let mut writer = BufWriter::new(Cursor::new(vec![0; 512]));
-        assert_eq!(player.hit_or_stay_strategy(BufReader::new(cursor), &mut writer), BlackjackAction::Stay);
+        assert_eq!(player.hit_or_stay_strategy(0, &HIT_OR_STAY, &no_view(), BufReader::new(cursor), &mut writer), BlackjackAction::Stay);
         let (recovered_writer, _buffered_data) = writer.into_parts();
         let captured_output = String::from_utf8(recovered_writer.into_inner()).unwrap_or(String::from("buffer write failed!"));
         assert_ne!(captured_output.len(), 0);
@@ -329,7 +646,7 @@ mod tests
 
         let cursor = Cursor::new(Vec::new());
         let mut writer = BufWriter::new(Cursor::new(vec![0; 512]));
-        assert_eq!(player.hit_or_stay_strategy(BufReader::new(cursor), &mut writer), BlackjackAction::Stay);
+        assert_eq!(player.hit_or_stay_strategy(0, &HIT_OR_STAY, &no_view(), BufReader::new(cursor), &mut writer), BlackjackAction::Stay);
         let (recovered_writer, _buffered_data) = writer.into_parts();
         let captured_output = String::from_utf8(recovered_writer.into_inner()).unwrap_or(String::from("buffer write failed!"));
         assert_ne!(captured_output.len(), 0);
@@ -429,21 +746,365 @@ mod tests
     fn test_dealer_hit_or_stay_strategy()
     {
         let mut player: Dealer = BlackjackPlayer::new("Dealer");
-        assert_eq!(player.hit_or_stay(), BlackjackAction::Hit);
+        assert_eq!(player.next_action(), BlackjackAction::Hit);
 
         player.add_card_to_hand(card::Card { rank: rank::Rank::Two, suit: suit::Suit::Diamonds});
-        assert_eq!(player.hit_or_stay(), BlackjackAction::Hit);
+        assert_eq!(player.next_action(), BlackjackAction::Hit);
 
         player.add_card_to_hand(card::Card { rank: rank::Rank::Jack, suit: suit::Suit::Clubs});
-        assert_eq!(player.hit_or_stay(), BlackjackAction::Hit);
+        assert_eq!(player.next_action(), BlackjackAction::Hit);
 
         player.add_card_to_hand(card::Card { rank: rank::Rank::Four, suit: suit::Suit::Hearts});
-        assert_eq!(player.hit_or_stay(), BlackjackAction::Hit);
+        assert_eq!(player.next_action(), BlackjackAction::Hit);
 
         player.add_card_to_hand(card::Card { rank: rank::Rank::Ace, suit: suit::Suit::Spades});
-        assert_eq!(player.hit_or_stay(), BlackjackAction::Stay);
+        assert_eq!(player.next_action(), BlackjackAction::Stay);
 
         player.add_card_to_hand(card::Card { rank: rank::Rank::Three, suit: suit::Suit::Clubs});
-        assert_eq!(player.hit_or_stay(), BlackjackAction::Stay);
+        assert_eq!(player.next_action(), BlackjackAction::Stay);
+    }
+
+    #[test]
+    fn test_human_player_hit_or_stay_strategy_split()
+    {
+        let mut player: HumanPlayer = BlackjackPlayer::new("Player");
+        player.add_card_to_hand(card::Card { rank: rank::Rank::Eight, suit: suit::Suit::Spades});
+        player.add_card_to_hand(card::Card { rank: rank::Rank::Eight, suit: suit::Suit::Clubs});
+        let with_split = [BlackjackAction::Hit, BlackjackAction::Stay, BlackjackAction::Split];
+
+        let cursor = Cursor::new(String::from("Split").into_bytes());
+        let mut writer = BufWriter::new(Cursor::new(vec![0; 512]));
+        assert_eq!(player.hit_or_stay_strategy(0, &with_split, &no_view(), BufReader::new(cursor), &mut writer), BlackjackAction::Split);
+        let (recovered_writer, _buffered_data) = writer.into_parts();
+        let captured_output = String::from_utf8(recovered_writer.into_inner()).unwrap_or(String::from("buffer write failed!"));
+        assert!(captured_output.contains("Player's hand: "));
+        assert!(captured_output.contains("Hit, Stay or Split?"));
+        assert!(captured_output.contains("Okay, you want to split."));
+
+        let cursor = Cursor::new(String::from("split\nhit\n").into_bytes());
+        let mut writer = BufWriter::new(Cursor::new(vec![0; 512]));
+        assert_eq!(player.hit_or_stay_strategy(0, &HIT_OR_STAY, &no_view(), BufReader::new(cursor), &mut writer), BlackjackAction::Hit);
+        let (recovered_writer, _buffered_data) = writer.into_parts();
+        let captured_output = String::from_utf8(recovered_writer.into_inner()).unwrap_or(String::from("buffer write failed!"));
+        assert!(captured_output.contains("Hit or Stay?"));
+        assert!(!captured_output.contains("Hit, Stay or Split?"));
+        assert!(captured_output.contains("You can't split this hand."));
+        assert!(captured_output.contains("Okay, you want to hit."));
+
+        player.split_hand(0);
+        let cursor = Cursor::new(String::from("stay").into_bytes());
+        let mut writer = BufWriter::new(Cursor::new(vec![0; 512]));
+        assert_eq!(player.hit_or_stay_strategy(1, &HIT_OR_STAY, &no_view(), BufReader::new(cursor), &mut writer), BlackjackAction::Stay);
+        let (recovered_writer, _buffered_data) = writer.into_parts();
+        let captured_output = String::from_utf8(recovered_writer.into_inner()).unwrap_or(String::from("buffer write failed!"));
+        assert!(captured_output.contains("Player's hand 2 of 2: "));
+    }
+
+    #[test]
+    fn test_human_player_split_hand()
+    {
+        let mut player: HumanPlayer = BlackjackPlayer::new("Player");
+        player.add_card_to_hand(card::Card { rank: rank::Rank::Eight, suit: suit::Suit::Spades});
+        player.add_card_to_hand(card::Card { rank: rank::Rank::Eight, suit: suit::Suit::Clubs});
+
+        player.split_hand(0);
+
+        assert_eq!(player.hands().len(), 2);
+        assert_eq!(player.hands()[0].cards(), [card::Card { rank: rank::Rank::Eight, suit: suit::Suit::Spades}]);
+        assert_eq!(player.hands()[1].cards(), [card::Card { rank: rank::Rank::Eight, suit: suit::Suit::Clubs}]);
+
+        player.hands_mut()[1].add_card_to_hand(card::Card { rank: rank::Rank::Two, suit: suit::Suit::Diamonds});
+        let discarded_hand = player.discard_hand();
+
+        assert_eq!(discarded_hand.len(), 3);
+        assert_eq!(player.hands().len(), 1);
+        assert_eq!(player.get_num_cards(), 0);
+        assert!(!player.hand().is_split());
+    }
+
+    #[test]
+    fn test_human_player_hit_or_stay_strategy_double()
+    {
+        let mut player: HumanPlayer = BlackjackPlayer::new("Player");
+        player.add_card_to_hand(card::Card { rank: rank::Rank::Six, suit: suit::Suit::Spades});
+        player.add_card_to_hand(card::Card { rank: rank::Rank::Five, suit: suit::Suit::Clubs});
+        let with_double = [BlackjackAction::Hit, BlackjackAction::Stay, BlackjackAction::Double];
+
+        for input in ["double", "D"]
+        {
+            let cursor = Cursor::new(String::from(input).into_bytes());
+            let mut writer = BufWriter::new(Cursor::new(vec![0; 512]));
+            assert_eq!(player.hit_or_stay_strategy(0, &with_double, &no_view(), BufReader::new(cursor), &mut writer), BlackjackAction::Double);
+            let (recovered_writer, _buffered_data) = writer.into_parts();
+            let captured_output = String::from_utf8(recovered_writer.into_inner()).unwrap_or(String::from("buffer write failed!"));
+            assert!(captured_output.contains("Hit, Stay or Double?"));
+            assert!(captured_output.contains("Okay, you want to double down."));
+        }
+
+        let cursor = Cursor::new(String::from("d\ndouble\nd\n").into_bytes());
+        let mut writer = BufWriter::new(Cursor::new(vec![0; 512]));
+        assert_eq!(player.hit_or_stay_strategy(0, &HIT_OR_STAY, &no_view(), BufReader::new(cursor), &mut writer), BlackjackAction::Stay);
+        let (recovered_writer, _buffered_data) = writer.into_parts();
+        let captured_output = String::from_utf8(recovered_writer.into_inner()).unwrap_or(String::from("buffer write failed!"));
+        assert_eq!(captured_output.matches("You can't double this hand.").collect::<Vec<_>>().len(), 3);
+        assert!(captured_output.contains("Let's just assume you want to stay."));
+    }
+
+    #[test]
+    fn test_human_player_hit_or_stay_strategy_surrender()
+    {
+        let mut player: HumanPlayer = BlackjackPlayer::new("Player");
+        player.add_card_to_hand(card::Card { rank: rank::Rank::Ten, suit: suit::Suit::Spades});
+        player.add_card_to_hand(card::Card { rank: rank::Rank::Six, suit: suit::Suit::Clubs});
+        let with_surrender = [BlackjackAction::Hit, BlackjackAction::Stay, BlackjackAction::Surrender];
+
+        let cursor = Cursor::new(String::from("Surrender").into_bytes());
+        let mut writer = BufWriter::new(Cursor::new(vec![0; 512]));
+        assert_eq!(player.hit_or_stay_strategy(0, &with_surrender, &no_view(), BufReader::new(cursor), &mut writer), BlackjackAction::Surrender);
+        let (recovered_writer, _buffered_data) = writer.into_parts();
+        let captured_output = String::from_utf8(recovered_writer.into_inner()).unwrap_or(String::from("buffer write failed!"));
+        assert!(captured_output.contains("Hit, Stay or Surrender?"));
+        assert!(captured_output.contains("Okay, you want to surrender."));
+
+        let cursor = Cursor::new(String::from("surrender\nstay\n").into_bytes());
+        let mut writer = BufWriter::new(Cursor::new(vec![0; 512]));
+        assert_eq!(player.hit_or_stay_strategy(0, &HIT_OR_STAY, &no_view(), BufReader::new(cursor), &mut writer), BlackjackAction::Stay);
+        let (recovered_writer, _buffered_data) = writer.into_parts();
+        let captured_output = String::from_utf8(recovered_writer.into_inner()).unwrap_or(String::from("buffer write failed!"));
+        assert!(captured_output.contains("You can't surrender this hand."));
+        assert!(captured_output.contains("Okay, you want to stay."));
+    }
+
+    #[test]
+    fn test_human_player_take_insurance_strategy()
+    {
+        let player: HumanPlayer = BlackjackPlayer::new("Player");
+
+        let cursor = Cursor::new(String::from("yes").into_bytes());
+        let mut writer = BufWriter::new(Cursor::new(vec![0; 512]));
+        assert!(player.take_insurance_strategy(false, BufReader::new(cursor), &mut writer));
+        let (recovered_writer, _buffered_data) = writer.into_parts();
+        let captured_output = String::from_utf8(recovered_writer.into_inner()).unwrap_or(String::from("buffer write failed!"));
+        assert!(captured_output.contains("Take insurance? (yes/no)"));
+        assert!(captured_output.contains("Okay, you're insured."));
+
+        let cursor = Cursor::new(String::from("n").into_bytes());
+        let mut writer = BufWriter::new(Cursor::new(vec![0; 512]));
+        assert!(!player.take_insurance_strategy(true, BufReader::new(cursor), &mut writer));
+        let (recovered_writer, _buffered_data) = writer.into_parts();
+        let captured_output = String::from_utf8(recovered_writer.into_inner()).unwrap_or(String::from("buffer write failed!"));
+        assert!(captured_output.contains("Take even money? (yes/no)"));
+        assert!(captured_output.contains("Okay, no insurance."));
+
+        let cursor = Cursor::new(String::from("asdf\njkl;\nqwer\n").into_bytes());
+        let mut writer = BufWriter::new(Cursor::new(vec![0; 512]));
+        assert!(!player.take_insurance_strategy(false, BufReader::new(cursor), &mut writer));
+        let (recovered_writer, _buffered_data) = writer.into_parts();
+        let captured_output = String::from_utf8(recovered_writer.into_inner()).unwrap_or(String::from("buffer write failed!"));
+        assert_eq!(captured_output.matches("That didn't make any sense...").collect::<Vec<_>>().len(), 3);
+        assert!(captured_output.contains("Let's just assume you don't want insurance."));
+    }
+
+    #[test]
+    fn test_dealer_up_card()
+    {
+        let mut dealer: Dealer = BlackjackPlayer::new("Dealer");
+        assert_eq!(dealer.up_card(), None);
+
+        dealer.add_card_to_hand(card::Card { rank: rank::Rank::Ace, suit: suit::Suit::Spades});
+        dealer.add_card_to_hand(card::Card { rank: rank::Rank::Jack, suit: suit::Suit::Clubs});
+        assert_eq!(dealer.up_card(), Some(card::Card { rank: rank::Rank::Ace, suit: suit::Suit::Spades}));
+    }
+
+    #[test]
+    fn test_dealer_hits_soft_17()
+    {
+        let h17 = rules::TableRules { dealer_hits_soft_17: true, ..rules::TableRules::default() };
+
+        let mut dealer = Dealer::with_rules(&h17);
+        dealer.add_card_to_hand(card::Card { rank: rank::Rank::Ace, suit: suit::Suit::Spades});
+        dealer.add_card_to_hand(card::Card { rank: rank::Rank::Six, suit: suit::Suit::Clubs});
+        assert_eq!(dealer.next_action(), BlackjackAction::Hit);
+        dealer.add_card_to_hand(card::Card { rank: rank::Rank::Ten, suit: suit::Suit::Clubs});
+        assert_eq!(dealer.get_point_value(), 17);
+        assert_eq!(dealer.next_action(), BlackjackAction::Stay);
+        dealer.add_card_to_hand(card::Card { rank: rank::Rank::Ace, suit: suit::Suit::Hearts});
+        assert_eq!(dealer.next_action(), BlackjackAction::Stay);
+
+        let mut dealer = Dealer::with_rules(&h17);
+        dealer.add_card_to_hand(card::Card { rank: rank::Rank::Ace, suit: suit::Suit::Spades});
+        dealer.add_card_to_hand(card::Card { rank: rank::Rank::Seven, suit: suit::Suit::Clubs});
+        assert_eq!(dealer.next_action(), BlackjackAction::Stay);
+
+        let mut dealer = Dealer::with_rules(&rules::TableRules::default());
+        dealer.add_card_to_hand(card::Card { rank: rank::Rank::Ace, suit: suit::Suit::Spades});
+        dealer.add_card_to_hand(card::Card { rank: rank::Rank::Six, suit: suit::Suit::Clubs});
+        assert_eq!(dealer.next_action(), BlackjackAction::Stay);
+    }
+
+    #[test]
+    fn test_human_player_ask_bet_strategy()
+    {
+        let rules = rules::TableRules { min_bet: 10, max_bet: 500, ..rules::TableRules::default() };
+        let mut player = HumanPlayer::with_bankroll("Player", 200);
+        assert_eq!(player.bankroll().balance(), 200);
+
+        let cursor = Cursor::new(String::from("50").into_bytes());
+        let mut writer = BufWriter::new(Cursor::new(vec![0; 512]));
+        assert_eq!(player.ask_bet_strategy(&rules, BufReader::new(cursor), &mut writer), 50);
+        assert_eq!(player.bet(), 50);
+        let (recovered_writer, _buffered_data) = writer.into_parts();
+        let captured_output = String::from_utf8(recovered_writer.into_inner()).unwrap_or(String::from("buffer write failed!"));
+        assert!(captured_output.contains("Player has 200 chips."));
+        assert!(captured_output.contains("Place your bet (10-500, Enter for 10): "));
+        assert!(captured_output.contains("Okay, you bet 50 chips."));
+
+        let cursor = Cursor::new(String::from("\n").into_bytes());
+        let mut writer = BufWriter::new(Cursor::new(vec![0; 512]));
+        assert_eq!(player.ask_bet_strategy(&rules, BufReader::new(cursor), &mut writer), 50);
+
+        let cursor = Cursor::new(String::from("5\n300\n20\n").into_bytes());
+        let mut writer = BufWriter::new(Cursor::new(vec![0; 1024]));
+        assert_eq!(player.ask_bet_strategy(&rules, BufReader::new(cursor), &mut writer), 20);
+        let (recovered_writer, _buffered_data) = writer.into_parts();
+        let captured_output = String::from_utf8(recovered_writer.into_inner()).unwrap_or(String::from("buffer write failed!"));
+        assert!(captured_output.contains("You can't bet 5 chips: the table minimum is 10 chips."));
+        assert!(captured_output.contains("You can't bet 300 chips: you only have 200 chips."));
+        assert!(captured_output.contains("Okay, you bet 20 chips."));
+
+        let cursor = Cursor::new(String::from("lots\n-5\n1000\n").into_bytes());
+        let mut writer = BufWriter::new(Cursor::new(vec![0; 1024]));
+        assert_eq!(player.ask_bet_strategy(&rules, BufReader::new(cursor), &mut writer), 20);
+        let (recovered_writer, _buffered_data) = writer.into_parts();
+        let captured_output = String::from_utf8(recovered_writer.into_inner()).unwrap_or(String::from("buffer write failed!"));
+        assert_eq!(captured_output.matches("That didn't make any sense...").collect::<Vec<_>>().len(), 2);
+        assert!(captured_output.contains("the table maximum is 500 chips"));
+        assert!(captured_output.contains("Let's just assume you want to bet 20 chips."));
+    }
+
+    #[test]
+    fn test_human_player_affordable_actions()
+    {
+        let rules = rules::TableRules { min_bet: 10, max_bet: 500, ..rules::TableRules::default() };
+        let all_actions = [BlackjackAction::Hit, BlackjackAction::Stay, BlackjackAction::Double, BlackjackAction::Split, BlackjackAction::Surrender];
+        let mut player = HumanPlayer::with_bankroll("Player", 150);
+        let cursor = Cursor::new(String::from("100").into_bytes());
+        player.ask_bet_strategy(&rules, BufReader::new(cursor), &mut BufWriter::new(Cursor::new(vec![0; 512])));
+        player.add_card_to_hand(card::Card { rank: rank::Rank::Eight, suit: suit::Suit::Spades});
+        player.add_card_to_hand(card::Card { rank: rank::Rank::Eight, suit: suit::Suit::Clubs});
+
+        assert_eq!(player.affordable_actions(&all_actions), [BlackjackAction::Hit, BlackjackAction::Stay, BlackjackAction::Surrender]);
+
+        let mut player = HumanPlayer::with_bankroll("Player", 200);
+        let cursor = Cursor::new(String::from("100").into_bytes());
+        player.ask_bet_strategy(&rules, BufReader::new(cursor), &mut BufWriter::new(Cursor::new(vec![0; 512])));
+        player.add_card_to_hand(card::Card { rank: rank::Rank::Eight, suit: suit::Suit::Spades});
+        player.add_card_to_hand(card::Card { rank: rank::Rank::Eight, suit: suit::Suit::Clubs});
+
+        assert_eq!(player.affordable_actions(&all_actions), all_actions);
+        player.split_hand(0);
+        assert_eq!(player.affordable_actions(&all_actions), [BlackjackAction::Hit, BlackjackAction::Stay, BlackjackAction::Surrender]);
+    }
+
+    #[test]
+    fn test_human_player_insurance_needs_chips()
+    {
+        let rules = rules::TableRules { min_bet: 10, max_bet: 500, ..rules::TableRules::default() };
+        let mut player = HumanPlayer::with_bankroll("Player", 120);
+        let cursor = Cursor::new(String::from("100").into_bytes());
+        player.ask_bet_strategy(&rules, BufReader::new(cursor), &mut BufWriter::new(Cursor::new(vec![0; 512])));
+
+        let cursor = Cursor::new(String::from("yes").into_bytes());
+        let mut writer = BufWriter::new(Cursor::new(vec![0; 512]));
+        assert!(!player.take_insurance_strategy(false, BufReader::new(cursor), &mut writer));
+        let (recovered_writer, _buffered_data) = writer.into_parts();
+        let captured_output = String::from_utf8(recovered_writer.into_inner()).unwrap_or(String::from("buffer write failed!"));
+        assert!(captured_output.contains("you don't have enough chips left for insurance"));
+    }
+
+    #[test]
+    fn test_human_player_settle_round()
+    {
+        let rules = rules::TableRules { min_bet: 10, max_bet: 500, ..rules::TableRules::default() };
+        let mut player = HumanPlayer::with_bankroll("Player", 100);
+        let cursor = Cursor::new(String::from("20").into_bytes());
+        player.ask_bet_strategy(&rules, BufReader::new(cursor), &mut BufWriter::new(Cursor::new(vec![0; 512])));
+
+        let mut blackjack = hand::Hand::new();
+        blackjack.add_card_to_hand(card::Card { rank: rank::Rank::Ace, suit: suit::Suit::Spades});
+        blackjack.add_card_to_hand(card::Card { rank: rank::Rank::King, suit: suit::Suit::Clubs});
+        let result = table::RoundResult {
+            hands: vec![table::HandResult { hand: blackjack, outcome: crate::outcome::Outcome::PlayerBlackjack, net: 1.5 }],
+            dealer_hand: hand::Hand::new(),
+            insurance: None,
+        };
+
+        assert_eq!(player.settle_round(&result), 30);
+        assert_eq!(player.bankroll().balance(), 130);
+    }
+
+    #[test]
+    fn test_human_player_hit_or_stay_strategy_shows_up_card()
+    {
+        let mut player: HumanPlayer = BlackjackPlayer::new("Player");
+        player.add_card_to_hand(card::Card { rank: rank::Rank::Ten, suit: suit::Suit::Spades});
+        player.add_card_to_hand(card::Card { rank: rank::Rank::Six, suit: suit::Suit::Clubs});
+        let view = table::TableView { dealer_up_card: Some(card::Card { rank: rank::Rank::Nine, suit: suit::Suit::Hearts}), ..no_view() };
+
+        let cursor = Cursor::new(String::from("stay").into_bytes());
+        let mut writer = BufWriter::new(Cursor::new(vec![0; 512]));
+        assert_eq!(player.hit_or_stay_strategy(0, &HIT_OR_STAY, &view, BufReader::new(cursor), &mut writer), BlackjackAction::Stay);
+        let (recovered_writer, _buffered_data) = writer.into_parts();
+        let captured_output = String::from_utf8(recovered_writer.into_inner()).unwrap_or(String::from("buffer write failed!"));
+        assert!(captured_output.contains("Dealer shows: 9 of Hearts"));
+
+        let cursor = Cursor::new(String::from("stay").into_bytes());
+        let mut writer = BufWriter::new(Cursor::new(vec![0; 512]));
+        player.hit_or_stay_strategy(0, &HIT_OR_STAY, &no_view(), BufReader::new(cursor), &mut writer);
+        let (recovered_writer, _buffered_data) = writer.into_parts();
+        let captured_output = String::from_utf8(recovered_writer.into_inner()).unwrap_or(String::from("buffer write failed!"));
+        assert!(!captured_output.contains("Dealer shows"));
+    }
+
+    #[test]
+    fn test_human_player_coach()
+    {
+        let mut player: HumanPlayer = BlackjackPlayer::new("Player");
+        player.add_card_to_hand(card::Card { rank: rank::Rank::Ten, suit: suit::Suit::Spades});
+        player.add_card_to_hand(card::Card { rank: rank::Rank::Six, suit: suit::Suit::Clubs});
+        let view = table::TableView { dealer_up_card: Some(card::Card { rank: rank::Rank::King, suit: suit::Suit::Hearts}), ..no_view() };
+        assert_eq!(player.coach_accuracy(), None);
+
+        let cursor = Cursor::new(String::from("stay").into_bytes());
+        let mut writer = BufWriter::new(Cursor::new(vec![0; 512]));
+        player.hit_or_stay_strategy(0, &HIT_OR_STAY, &view, BufReader::new(cursor), &mut writer);
+        let (recovered_writer, _buffered_data) = writer.into_parts();
+        let captured_output = String::from_utf8(recovered_writer.into_inner()).unwrap_or(String::from("buffer write failed!"));
+        assert!(!captured_output.contains("Coach"));
+        assert_eq!(player.coached_decisions(), 0);
+
+        player.set_coach(true);
+        let cursor = Cursor::new(String::from("stay").into_bytes());
+        let mut writer = BufWriter::new(Cursor::new(vec![0; 512]));
+        player.hit_or_stay_strategy(0, &HIT_OR_STAY, &view, BufReader::new(cursor), &mut writer);
+        let (recovered_writer, _buffered_data) = writer.into_parts();
+        let captured_output = String::from_utf8(recovered_writer.into_inner()).unwrap_or(String::from("buffer write failed!"));
+        assert!(captured_output.contains("Coach: Basic strategy says hit with hard 16 against a dealer King"));
+        assert_eq!(player.coach_accuracy(), Some(0.0));
+
+        let cursor = Cursor::new(String::from("hit").into_bytes());
+        let mut writer = BufWriter::new(Cursor::new(vec![0; 512]));
+        player.hit_or_stay_strategy(0, &HIT_OR_STAY, &view, BufReader::new(cursor), &mut writer);
+        let (recovered_writer, _buffered_data) = writer.into_parts();
+        let captured_output = String::from_utf8(recovered_writer.into_inner()).unwrap_or(String::from("buffer write failed!"));
+        assert!(!captured_output.contains("Coach"));
+        assert_eq!(player.coached_decisions(), 2);
+        assert_eq!(player.coach_accuracy(), Some(50.0));
+
+        let cursor = Cursor::new(String::from("nope\nnope\nnope\n").into_bytes());
+        let mut writer = BufWriter::new(Cursor::new(vec![0; 512]));
+        player.hit_or_stay_strategy(0, &HIT_OR_STAY, &view, BufReader::new(cursor), &mut writer);
+        player.hit_or_stay_strategy(0, &HIT_OR_STAY, &no_view(), BufReader::new(Cursor::new(String::from("hit").into_bytes())), &mut writer);
+        assert_eq!(player.coached_decisions(), 2);
     }
 }
@@ -1,4 +1,5 @@
 use std::fmt;
+use std::str;
 use std::convert;
 use std::error;
 
@@ -15,6 +16,19 @@ impl fmt::Display for InvalidIntToRank
 
 impl error::Error for InvalidIntToRank {}
 
+#[derive(Debug, Eq, PartialEq)]
+pub struct InvalidRankNotation;
+
+impl fmt::Display for InvalidRankNotation
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        write!(f, "invalid rank; expected one of A, 2-10, T, J, Q, K")
+    }
+}
+
+impl error::Error for InvalidRankNotation {}
+
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum Rank
 {
@@ -37,6 +51,10 @@ impl fmt::Display for Rank
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
     {
+        if f.alternate()
+        {
+            return write!(f, "{}", self.symbol());
+        }
         match &self
         {
             Rank::Ace => write!(f, "Ace"),
@@ -56,6 +74,55 @@ impl fmt::Display for Rank
     }
 }
 
+impl str::FromStr for Rank
+{
+    type Err = InvalidRankNotation;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err>
+    {
+        match s.trim().to_uppercase().as_str()
+        {
+            "A" | "ACE" => Ok(Rank::Ace),
+            "2" => Ok(Rank::Two),
+            "3" => Ok(Rank::Three),
+            "4" => Ok(Rank::Four),
+            "5" => Ok(Rank::Five),
+            "6" => Ok(Rank::Six),
+            "7" => Ok(Rank::Seven),
+            "8" => Ok(Rank::Eight),
+            "9" => Ok(Rank::Nine),
+            "10" | "T" => Ok(Rank::Ten),
+            "J" | "JACK" => Ok(Rank::Jack),
+            "Q" | "QUEEN" => Ok(Rank::Queen),
+            "K" | "KING" => Ok(Rank::King),
+            _ => Err(InvalidRankNotation)
+        }
+    }
+}
+
+impl Rank
+{
+    pub fn symbol(&self) -> &'static str
+    {
+        match &self
+        {
+            Rank::Ace => "A",
+            Rank::Two => "2",
+            Rank::Three => "3",
+            Rank::Four => "4",
+            Rank::Five => "5",
+            Rank::Six => "6",
+            Rank::Seven => "7",
+            Rank::Eight => "8",
+            Rank::Nine => "9",
+            Rank::Ten => "T",
+            Rank::Jack => "J",
+            Rank::Queen => "Q",
+            Rank::King => "K",
+        }
+    }
+}
+
 impl convert::TryFrom<u32> for Rank
 {
     type Error = InvalidIntToRank;
@@ -125,12 +192,46 @@ mod tests
         for n in 0..20
         {
             let try_rank_from_u32 = Rank::try_from(n);
-            let expected_okay = match Some(n)
-            {
-                Some(x) if x >= Rank::Ace as u32 && x <= Rank::King as u32 => true,
-                _ => false,
-            };
+            let expected_okay = matches!(Some(n), Some(x) if x >= Rank::Ace as u32 && x <= Rank::King as u32);
             assert_eq!(try_rank_from_u32.is_ok(), expected_okay);
         }
     }
+
+    #[test]
+    fn test_format_rank_compact()
+    {
+        assert_eq!(format!("{:#}", Rank::Ace), "A");
+        assert_eq!(format!("{:#}", Rank::Two), "2");
+        assert_eq!(format!("{:#}", Rank::Ten), "T");
+        assert_eq!(format!("{:#}", Rank::Jack), "J");
+        assert_eq!(format!("{:#}", Rank::Queen), "Q");
+        assert_eq!(format!("{:#}", Rank::King), "K");
+    }
+
+    #[test]
+    fn test_rank_from_str()
+    {
+        assert_eq!("A".parse::<Rank>(), Ok(Rank::Ace));
+        assert_eq!("ace".parse::<Rank>(), Ok(Rank::Ace));
+        assert_eq!("7".parse::<Rank>(), Ok(Rank::Seven));
+        assert_eq!("10".parse::<Rank>(), Ok(Rank::Ten));
+        assert_eq!("t".parse::<Rank>(), Ok(Rank::Ten));
+        assert_eq!("J".parse::<Rank>(), Ok(Rank::Jack));
+        assert_eq!("q".parse::<Rank>(), Ok(Rank::Queen));
+        assert_eq!("King".parse::<Rank>(), Ok(Rank::King));
+        assert_eq!("1".parse::<Rank>(), Err(InvalidRankNotation));
+        assert_eq!("11".parse::<Rank>(), Err(InvalidRankNotation));
+        assert_eq!("".parse::<Rank>(), Err(InvalidRankNotation));
+    }
+
+    #[test]
+    fn test_rank_round_trip()
+    {
+        for n in Rank::Ace as u32..=Rank::King as u32
+        {
+            let rank = Rank::try_from(n).unwrap();
+            assert_eq!(format!("{:#}", rank).parse::<Rank>(), Ok(rank));
+            assert_eq!(format!("{}", rank).parse::<Rank>(), Ok(rank));
+        }
+    }
 }
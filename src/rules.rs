@@ -0,0 +1,397 @@
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum DoubleOn
+{
+    AnyTwoCards,
+    NineToEleven,
+    TenToEleven,
+    Never,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct DoubleRules
+{
+    pub on: DoubleOn,
+    pub after_split: bool,
+}
+
+impl Default for DoubleRules
+{
+    fn default() -> Self
+    {
+        DoubleRules { on: DoubleOn::AnyTwoCards, after_split: true }
+    }
+}
+
+impl DoubleRules
+{
+    pub fn allows_total(&self, total: u32) -> bool
+    {
+        match self.on
+        {
+            DoubleOn::AnyTwoCards => true,
+            DoubleOn::NineToEleven => (9..=11).contains(&total),
+            DoubleOn::TenToEleven => (10..=11).contains(&total),
+            DoubleOn::Never => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum SurrenderRule
+{
+    NoSurrender,
+    Late,
+    Early,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct SplitRules
+{
+    pub max_hands: u32,
+    pub resplit_aces: bool,
+    pub hit_split_aces: bool,
+    pub blackjack_after_split: bool,
+}
+
+impl Default for SplitRules
+{
+    fn default() -> Self
+    {
+        SplitRules { max_hands: 4, resplit_aces: false, hit_split_aces: false, blackjack_after_split: false }
+    }
+}
+
+impl SplitRules
+{
+    pub fn no_splits() -> SplitRules
+    {
+        SplitRules { max_hands: 1, ..SplitRules::default() }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum BlackjackPayout
+{
+    ThreeToTwo,
+    SixToFive,
+    EvenMoney,
+}
+
+impl fmt::Display for BlackjackPayout
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match &self
+        {
+            BlackjackPayout::ThreeToTwo => write!(f, "3:2"),
+            BlackjackPayout::SixToFive => write!(f, "6:5"),
+            BlackjackPayout::EvenMoney => write!(f, "1:1"),
+        }
+    }
+}
+
+impl BlackjackPayout
+{
+    pub fn ratio(&self) -> f64
+    {
+        match &self
+        {
+            BlackjackPayout::ThreeToTwo => 1.5,
+            BlackjackPayout::SixToFive => 1.2,
+            BlackjackPayout::EvenMoney => 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct TableRules
+{
+    pub dealer_hits_soft_17: bool,
+    pub blackjack_payout: BlackjackPayout,
+    pub decks: u32,
+    pub dealer_peeks: bool,
+    pub double: DoubleRules,
+    pub split: SplitRules,
+    pub surrender: SurrenderRule,
+    pub min_bet: u32,
+    pub max_bet: u32,
+}
+
+impl Default for TableRules
+{
+    fn default() -> Self
+    {
+        TableRules::vegas_strip()
+    }
+}
+
+impl fmt::Display for TableRules
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        write!(f, "{} deck(s), ", self.decks)?;
+        write!(f, "{}, ", if self.dealer_hits_soft_17 { "H17" } else { "S17" })?;
+        write!(f, "Blackjack pays {}, ", self.blackjack_payout)?;
+        write!(f, "{}, ", if self.dealer_peeks { "dealer peeks" } else { "no hole card" })?;
+        match self.double.on
+        {
+            DoubleOn::AnyTwoCards => write!(f, "double any two cards, ")?,
+            DoubleOn::NineToEleven => write!(f, "double 9-11, ")?,
+            DoubleOn::TenToEleven => write!(f, "double 10-11, ")?,
+            DoubleOn::Never => write!(f, "no doubling, ")?,
+        };
+        write!(f, "{}, ", if self.double.after_split { "DAS" } else { "no DAS" })?;
+        write!(f, "split to {} hands, ", self.split.max_hands)?;
+        match self.surrender
+        {
+            SurrenderRule::NoSurrender => write!(f, "no surrender"),
+            SurrenderRule::Late => write!(f, "late surrender"),
+            SurrenderRule::Early => write!(f, "early surrender"),
+        }
+    }
+}
+
+impl TableRules
+{
+    pub fn vegas_strip() -> TableRules
+    {
+        TableRules {
+            dealer_hits_soft_17: false,
+            blackjack_payout: BlackjackPayout::ThreeToTwo,
+            decks: 6,
+            dealer_peeks: true,
+            double: DoubleRules { on: DoubleOn::AnyTwoCards, after_split: true },
+            split: SplitRules { max_hands: 4, ..SplitRules::default() },
+            surrender: SurrenderRule::NoSurrender,
+            min_bet: 25,
+            max_bet: 5000,
+        }
+    }
+
+    pub fn atlantic_city() -> TableRules
+    {
+        TableRules {
+            dealer_hits_soft_17: false,
+            blackjack_payout: BlackjackPayout::ThreeToTwo,
+            decks: 8,
+            dealer_peeks: true,
+            double: DoubleRules { on: DoubleOn::AnyTwoCards, after_split: true },
+            split: SplitRules { max_hands: 2, ..SplitRules::default() },
+            surrender: SurrenderRule::Late,
+            min_bet: 15,
+            max_bet: 2000,
+        }
+    }
+
+    pub fn downtown() -> TableRules
+    {
+        TableRules {
+            dealer_hits_soft_17: true,
+            blackjack_payout: BlackjackPayout::ThreeToTwo,
+            decks: 2,
+            dealer_peeks: true,
+            double: DoubleRules { on: DoubleOn::AnyTwoCards, after_split: true },
+            split: SplitRules { max_hands: 4, ..SplitRules::default() },
+            surrender: SurrenderRule::NoSurrender,
+            min_bet: 5,
+            max_bet: 500,
+        }
+    }
+
+    pub fn european() -> TableRules
+    {
+        TableRules {
+            dealer_hits_soft_17: false,
+            blackjack_payout: BlackjackPayout::ThreeToTwo,
+            decks: 6,
+            dealer_peeks: false,
+            double: DoubleRules { on: DoubleOn::NineToEleven, after_split: true },
+            split: SplitRules { max_hands: 2, ..SplitRules::default() },
+            surrender: SurrenderRule::NoSurrender,
+            min_bet: 10,
+            max_bet: 1000,
+        }
+    }
+
+    pub fn preset(name: &str) -> Option<TableRules>
+    {
+        match name.to_lowercase().as_str().trim()
+        {
+            "vegas" | "vegas-strip" | "strip" => Some(TableRules::vegas_strip()),
+            "atlantic-city" | "ac" => Some(TableRules::atlantic_city()),
+            "downtown" => Some(TableRules::downtown()),
+            "european" | "enhc" => Some(TableRules::european()),
+            _ => None
+        }
+    }
+}
+
+// Collects the rule flags shared by the command line tools; the deck and soft 17 flags apply on top of any preset.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct RuleOptions
+{
+    preset: Option<TableRules>,
+    decks: Option<u32>,
+    hits_soft_17: Option<bool>,
+}
+
+impl RuleOptions
+{
+    pub fn parse_arg<I: Iterator<Item = String>>(&mut self, name: &str, value: Option<String>, args: &mut I) -> Result<bool, String>
+    {
+        match name
+        {
+            "--rules" => {
+                self.preset = match value.or_else(|| args.next()).and_then(|value| TableRules::preset(&value))
+                {
+                    Some(preset) => Some(preset),
+                    None => return Err(String::from("--rules expects vegas, atlantic-city, downtown or european")),
+                };
+            }
+            "--decks" => {
+                self.decks = match value.or_else(|| args.next()).map(|value| value.parse::<u32>())
+                {
+                    Some(Ok(decks)) if (1..=8).contains(&decks) => Some(decks),
+                    _ => return Err(String::from("--decks expects a number from 1 to 8")),
+                };
+            }
+            "--h17" if value.is_none() => self.hits_soft_17 = Some(true),
+            "--s17" if value.is_none() => self.hits_soft_17 = Some(false),
+            _ => return Ok(false),
+        };
+        Ok(true)
+    }
+
+    pub fn rules(&self) -> TableRules
+    {
+        let mut rules = self.preset.unwrap_or_default();
+        if let Some(decks) = self.decks
+        {
+            rules.decks = decks;
+        }
+        if let Some(hits_soft_17) = self.hits_soft_17
+        {
+            rules.dealer_hits_soft_17 = hits_soft_17;
+        }
+        rules
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn test_blackjack_payout_ratio()
+    {
+        assert_eq!(BlackjackPayout::ThreeToTwo.ratio(), 1.5);
+        assert_eq!(BlackjackPayout::SixToFive.ratio(), 1.2);
+        assert_eq!(BlackjackPayout::EvenMoney.ratio(), 1.0);
+        assert_eq!(format!("{}", BlackjackPayout::ThreeToTwo), "3:2");
+        assert_eq!(format!("{}", BlackjackPayout::SixToFive), "6:5");
+        assert_eq!(format!("{}", BlackjackPayout::EvenMoney), "1:1");
+    }
+
+    #[test]
+    fn test_presets()
+    {
+        assert_eq!(TableRules::default(), TableRules::vegas_strip());
+        assert!(!TableRules::vegas_strip().dealer_hits_soft_17);
+        assert!(TableRules::downtown().dealer_hits_soft_17);
+        assert_eq!(TableRules::atlantic_city().decks, 8);
+        assert_eq!(TableRules::atlantic_city().surrender, SurrenderRule::Late);
+        assert!(!TableRules::european().dealer_peeks);
+        assert_eq!(TableRules::european().double.on, DoubleOn::NineToEleven);
+        for rules in [TableRules::vegas_strip(), TableRules::atlantic_city(), TableRules::downtown(), TableRules::european()]
+        {
+            assert!(rules.min_bet > 0 && rules.min_bet < rules.max_bet);
+        }
+    }
+
+    #[test]
+    fn test_preset_by_name()
+    {
+        assert_eq!(TableRules::preset("Vegas"), Some(TableRules::vegas_strip()));
+        assert_eq!(TableRules::preset("atlantic-city"), Some(TableRules::atlantic_city()));
+        assert_eq!(TableRules::preset("downtown"), Some(TableRules::downtown()));
+        assert_eq!(TableRules::preset("European"), Some(TableRules::european()));
+        assert_eq!(TableRules::preset("reno"), None);
+    }
+
+    #[test]
+    fn test_format_table_rules()
+    {
+        let formatted_string = format!("{}", TableRules::vegas_strip());
+        assert_eq!(formatted_string, "6 deck(s), S17, Blackjack pays 3:2, dealer peeks, double any two cards, DAS, split to 4 hands, no surrender");
+        let formatted_string = format!("{}", TableRules::european());
+        assert!(formatted_string.contains("no hole card"));
+        assert!(formatted_string.contains("double 9-11"));
+    }
+
+    #[test]
+    fn test_double_rules_allows_total()
+    {
+        let any_two_cards = DoubleRules::default();
+        let nine_to_eleven = DoubleRules { on: DoubleOn::NineToEleven, ..DoubleRules::default() };
+        let ten_to_eleven = DoubleRules { on: DoubleOn::TenToEleven, ..DoubleRules::default() };
+        let never = DoubleRules { on: DoubleOn::Never, ..DoubleRules::default() };
+
+        for total in 4..=21
+        {
+            assert!(any_two_cards.allows_total(total));
+            assert_eq!(nine_to_eleven.allows_total(total), total == 9 || total == 10 || total == 11);
+            assert_eq!(ten_to_eleven.allows_total(total), total == 10 || total == 11);
+            assert!(!never.allows_total(total));
+        }
+    }
+
+    #[test]
+    fn test_default_split_rules()
+    {
+        let rules = SplitRules::default();
+        assert_eq!(rules.max_hands, 4);
+        assert!(!rules.resplit_aces);
+        assert!(!rules.hit_split_aces);
+        assert!(!rules.blackjack_after_split);
+    }
+
+    #[test]
+    fn test_no_splits()
+    {
+        assert_eq!(SplitRules::no_splits().max_hands, 1);
+    }
+
+    #[test]
+    fn test_rule_options()
+    {
+        let parse = |values: &[&str]| {
+            let mut args = values.iter().map(|value| value.to_string());
+            let mut options = RuleOptions::default();
+            while let Some(arg) = args.next()
+            {
+                let (name, value) = match arg.split_once('=')
+                {
+                    Some((name, value)) => (name.to_string(), Some(value.to_string())),
+                    None => (arg.clone(), None),
+                };
+                if !options.parse_arg(&name, value, &mut args)?
+                {
+                    return Err(format!("Unknown argument: {}", arg));
+                }
+            }
+            Ok(options.rules())
+        };
+
+        assert_eq!(parse(&[]), Ok(TableRules::default()));
+        assert_eq!(parse(&["--rules", "downtown"]), Ok(TableRules::downtown()));
+        assert_eq!(parse(&["--decks=2", "--rules", "ac"]).unwrap(), TableRules { decks: 2, ..TableRules::atlantic_city() });
+        assert!(parse(&["--h17"]).unwrap().dealer_hits_soft_17);
+        assert!(!parse(&["--s17", "--rules", "downtown"]).unwrap().dealer_hits_soft_17);
+        assert!(parse(&["--rules", "macau"]).is_err());
+        assert!(parse(&["--decks", "9"]).is_err());
+        assert!(parse(&["--h17=yes"]).is_err());
+    }
+}
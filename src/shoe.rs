@@ -0,0 +1,262 @@
+use rand::prelude::*;
+use rand::rngs::StdRng;
+
+use crate::card;
+use crate::deck;
+
+pub const DEFAULT_PENETRATION: f64 = 0.75;
+pub const DEFAULT_BURN_CARDS: u32 = 1;
+
+#[derive(Debug)]
+pub struct Shoe
+{
+    deck: deck::Deck,
+    discards: Vec<card::Card>,
+    num_decks: u32,
+    cut_card: usize,
+    burn_cards: u32,
+    cut_card_reached: bool,
+    seed: u64,
+    rng: StdRng,
+    stacked: bool,
+}
+
+impl Shoe
+{
+    pub fn new(num_decks: u32, penetration: f64, burn_cards: u32) -> Shoe
+    {
+        Shoe::with_seed(num_decks, penetration, burn_cards, rand::thread_rng().gen())
+    }
+
+    pub fn with_seed(num_decks: u32, penetration: f64, burn_cards: u32, seed: u64) -> Shoe
+    {
+        let deck = deck::Deck::with_decks(num_decks);
+        let penetration = penetration.clamp(0.0, 1.0);
+        let cut_card = deck.len() - (deck.len() as f64 * penetration).round() as usize;
+        Shoe { deck, discards: vec![], num_decks, cut_card, burn_cards, cut_card_reached: true, seed, rng: StdRng::seed_from_u64(seed), stacked: false }
+    }
+
+    pub fn stacked(deck: deck::Deck) -> Shoe
+    {
+        let num_decks = deck.len().div_ceil(52) as u32;
+        Shoe { deck, discards: vec![], num_decks, cut_card: 0, burn_cards: 0, cut_card_reached: false, seed: 0, rng: StdRng::seed_from_u64(0), stacked: true }
+    }
+
+    pub fn is_stacked(&self) -> bool
+    {
+        self.stacked
+    }
+
+    pub fn seed(&self) -> u64
+    {
+        self.seed
+    }
+
+    pub fn num_decks(&self) -> u32
+    {
+        self.num_decks
+    }
+
+    pub fn cards_remaining(&self) -> usize
+    {
+        self.deck.len()
+    }
+
+    pub fn decks_remaining(&self) -> f64
+    {
+        self.deck.len() as f64 / 52.0
+    }
+
+    pub fn cards_discarded(&self) -> usize
+    {
+        self.discards.len()
+    }
+
+    pub fn needs_shuffle(&self) -> bool
+    {
+        self.cut_card_reached
+    }
+
+    pub fn shuffle(&mut self)
+    {
+        self.deck.collect_played_cards(self.discards.drain(..).collect());
+        if !self.stacked
+        {
+            self.deck.shuffle_with_rng(&mut self.rng);
+        }
+        self.cut_card_reached = false;
+        for _ in 0..self.burn_cards
+        {
+            if let Ok(burned) = self.deck.deal_card()
+            {
+                self.discards.push(burned);
+            }
+        }
+    }
+
+    pub fn reshuffle_discards(&mut self)
+    {
+        self.deck.collect_played_cards(self.discards.drain(..).collect());
+        if !self.stacked
+        {
+            self.deck.shuffle_with_rng(&mut self.rng);
+        }
+        self.cut_card_reached = true;
+    }
+
+    pub fn deal_card(&mut self) -> Result<card::Card, deck::EmptyDeck>
+    {
+        let card = self.deck.deal_card()?;
+        if self.deck.len() <= self.cut_card
+        {
+            self.cut_card_reached = true;
+        }
+        Ok(card)
+    }
+
+    pub fn collect_played_cards(&mut self, mut played_cards: Vec<card::Card>)
+    {
+        self.discards.append(&mut played_cards);
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::rank;
+    use crate::suit;
+
+    #[test]
+    fn test_new_shoe()
+    {
+        for num_decks in [1, 2, 4, 6, 8]
+        {
+            let shoe = Shoe::new(num_decks, DEFAULT_PENETRATION, DEFAULT_BURN_CARDS);
+            assert_eq!(shoe.num_decks(), num_decks);
+            assert_eq!(shoe.cards_remaining(), 52 * num_decks as usize);
+            assert_eq!(shoe.decks_remaining(), num_decks as f64);
+            assert!(shoe.needs_shuffle());
+        }
+    }
+
+    #[test]
+    fn test_shuffle_burns_cards()
+    {
+        let mut shoe = Shoe::new(6, DEFAULT_PENETRATION, 1);
+        shoe.shuffle();
+        assert!(!shoe.needs_shuffle());
+        assert_eq!(shoe.cards_remaining(), 311);
+        assert_eq!(shoe.discards.len(), 1);
+
+        let mut shoe = Shoe::new(2, DEFAULT_PENETRATION, 0);
+        shoe.shuffle();
+        assert_eq!(shoe.cards_remaining(), 104);
+    }
+
+    #[test]
+    fn test_cut_card_penetration()
+    {
+        let mut shoe = Shoe::new(2, 0.75, 0);
+        shoe.shuffle();
+
+        for _ in 0..77
+        {
+            let card = shoe.deal_card().unwrap();
+            shoe.collect_played_cards(vec![card]);
+            assert!(!shoe.needs_shuffle());
+        }
+        let card = shoe.deal_card().unwrap();
+        shoe.collect_played_cards(vec![card]);
+        assert!(shoe.needs_shuffle());
+        assert_eq!(shoe.cards_remaining(), 26);
+    }
+
+    #[test]
+    fn test_shuffle_collects_discards()
+    {
+        let mut shoe = Shoe::new(1, 0.5, 1);
+        shoe.shuffle();
+        while !shoe.needs_shuffle()
+        {
+            let card = shoe.deal_card().unwrap();
+            shoe.collect_played_cards(vec![card]);
+        }
+        assert_eq!(shoe.cards_remaining(), 26);
+
+        shoe.shuffle();
+        assert_eq!(shoe.cards_remaining(), 51);
+        assert!(!shoe.needs_shuffle());
+    }
+
+    #[test]
+    fn test_deal_card_exhausts_shoe()
+    {
+        let mut shoe = Shoe::new(1, 1.0, 0);
+        shoe.shuffle();
+        let mut dealt = vec![];
+        for _ in 0..52
+        {
+            dealt.push(shoe.deal_card().unwrap());
+        }
+
+        assert!(shoe.needs_shuffle());
+        assert_eq!(shoe.deal_card(), Err(deck::EmptyDeck));
+
+        shoe.collect_played_cards(dealt.drain(..10).collect());
+        shoe.reshuffle_discards();
+        assert_eq!(shoe.cards_remaining(), 10);
+        assert_eq!(shoe.cards_discarded(), 0);
+        assert!(shoe.needs_shuffle());
+        for _ in 0..10
+        {
+            assert!(shoe.deal_card().is_ok());
+        }
+        assert_eq!(shoe.deal_card(), Err(deck::EmptyDeck));
+    }
+
+    #[test]
+    fn test_seeded_shoes_deal_identical_cards()
+    {
+        let mut first_shoe = Shoe::with_seed(6, DEFAULT_PENETRATION, DEFAULT_BURN_CARDS, 1234);
+        let mut second_shoe = Shoe::with_seed(6, DEFAULT_PENETRATION, DEFAULT_BURN_CARDS, 1234);
+        assert_eq!(first_shoe.seed(), 1234);
+
+        for _ in 0..3
+        {
+            first_shoe.shuffle();
+            second_shoe.shuffle();
+            while !first_shoe.needs_shuffle()
+            {
+                let first_card = first_shoe.deal_card().unwrap();
+                let second_card = second_shoe.deal_card().unwrap();
+                assert_eq!(first_card, second_card);
+                first_shoe.collect_played_cards(vec![first_card]);
+                second_shoe.collect_played_cards(vec![second_card]);
+            }
+            assert!(second_shoe.needs_shuffle());
+        }
+    }
+
+    #[test]
+    fn test_stacked_shoe_deals_in_order()
+    {
+        let mut shoe = Shoe::stacked(deck::Deck::from_notation("AS KD 8H").unwrap());
+        assert!(shoe.is_stacked());
+        assert!(!shoe.needs_shuffle());
+        assert_eq!(shoe.num_decks(), 1);
+
+        let first_card = shoe.deal_card().unwrap();
+        assert_eq!(first_card, card::Card { rank: rank::Rank::Ace, suit: suit::Suit::Spades });
+        assert!(!shoe.needs_shuffle());
+        shoe.collect_played_cards(vec![first_card]);
+        shoe.reshuffle_discards();
+        assert_eq!(shoe.cards_discarded(), 0);
+
+        assert_eq!(shoe.deal_card(), Ok(card::Card { rank: rank::Rank::King, suit: suit::Suit::Diamonds }));
+        assert_eq!(shoe.deal_card(), Ok(card::Card { rank: rank::Rank::Eight, suit: suit::Suit::Hearts }));
+        assert_eq!(shoe.deal_card(), Ok(first_card));
+        assert!(shoe.needs_shuffle());
+        assert_eq!(shoe.deal_card(), Err(deck::EmptyDeck));
+    }
+}
@@ -0,0 +1,381 @@
+use std::fmt;
+use std::thread;
+
+use crate::outcome;
+use crate::rules;
+use crate::player::BlackjackPlayer;
+use crate::table;
+
+pub const Z_95: f64 = 1.959964;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Statistics
+{
+    pub rounds: u64,
+    pub seat_rounds: u64,
+    pub hands: u64,
+    pub wins: u64,
+    pub losses: u64,
+    pub pushes: u64,
+    pub surrenders: u64,
+    pub player_blackjacks: u64,
+    pub dealer_blackjacks: u64,
+    total_net: f64,
+    total_net_squared: f64,
+}
+
+impl Statistics
+{
+    pub fn new() -> Statistics
+    {
+        Statistics::default()
+    }
+
+    // One sample per table round, since seats sharing a dealer hand are not independent.
+    pub fn record(&mut self, results: &[table::RoundResult])
+    {
+        let mut net = 0.0;
+        for result in results.iter()
+        {
+            net += result.insurance.unwrap_or(0.0) + self.record_seat(result);
+        }
+        self.rounds += 1;
+        self.seat_rounds += results.len() as u64;
+        self.total_net += net;
+        self.total_net_squared += net * net;
+    }
+
+    fn record_seat(&mut self, result: &table::RoundResult) -> f64
+    {
+        let mut net = 0.0;
+        for hand_result in result.hands.iter()
+        {
+            match hand_result.outcome
+            {
+                outcome::Outcome::Push => self.pushes += 1,
+                outcome::Outcome::Surrender => self.surrenders += 1,
+                outcome => match outcome.player_wins()
+                {
+                    true => self.wins += 1,
+                    false => self.losses += 1,
+                },
+            };
+            match hand_result.outcome
+            {
+                outcome::Outcome::PlayerBlackjack => self.player_blackjacks += 1,
+                outcome::Outcome::DealerBlackjack => self.dealer_blackjacks += 1,
+                _ => (),
+            };
+            net += hand_result.net;
+        }
+        self.hands += result.hands.len() as u64;
+        net
+    }
+
+    pub fn merge(&mut self, other: &Statistics)
+    {
+        self.rounds += other.rounds;
+        self.seat_rounds += other.seat_rounds;
+        self.hands += other.hands;
+        self.wins += other.wins;
+        self.losses += other.losses;
+        self.pushes += other.pushes;
+        self.surrenders += other.surrenders;
+        self.player_blackjacks += other.player_blackjacks;
+        self.dealer_blackjacks += other.dealer_blackjacks;
+        self.total_net += other.total_net;
+        self.total_net_squared += other.total_net_squared;
+    }
+
+    fn rate(&self, count: u64) -> f64
+    {
+        match self.hands
+        {
+            0 => 0.0,
+            hands => count as f64 / hands as f64,
+        }
+    }
+
+    pub fn win_rate(&self) -> f64
+    {
+        self.rate(self.wins)
+    }
+
+    pub fn loss_rate(&self) -> f64
+    {
+        self.rate(self.losses)
+    }
+
+    pub fn push_rate(&self) -> f64
+    {
+        self.rate(self.pushes)
+    }
+
+    pub fn surrender_rate(&self) -> f64
+    {
+        self.rate(self.surrenders)
+    }
+
+    pub fn blackjack_frequency(&self) -> f64
+    {
+        match self.seat_rounds
+        {
+            0 => 0.0,
+            seat_rounds => self.player_blackjacks as f64 / seat_rounds as f64,
+        }
+    }
+
+    pub fn total_net(&self) -> f64
+    {
+        self.total_net
+    }
+
+    pub fn expected_value(&self) -> f64
+    {
+        match self.seat_rounds
+        {
+            0 => 0.0,
+            seat_rounds => self.total_net / seat_rounds as f64,
+        }
+    }
+
+    pub fn std_dev(&self) -> f64
+    {
+        match self.rounds
+        {
+            0 | 1 => 0.0,
+            rounds => {
+                let n = rounds as f64;
+                ((self.total_net_squared - self.total_net * self.total_net / n) / (n - 1.0)).max(0.0).sqrt()
+            }
+        }
+    }
+
+    // The samples are whole table rounds, so scale their standard error down to one seat.
+    pub fn std_error(&self) -> f64
+    {
+        match self.seat_rounds
+        {
+            0 => 0.0,
+            seat_rounds => self.std_dev() * (self.rounds as f64).sqrt() / seat_rounds as f64,
+        }
+    }
+
+    pub fn confidence_interval(&self, z: f64) -> (f64, f64)
+    {
+        let margin = z * self.std_error();
+        (self.expected_value() - margin, self.expected_value() + margin)
+    }
+}
+
+impl fmt::Display for Statistics
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        let (low, high) = self.confidence_interval(Z_95);
+        writeln!(f, "Rounds played:           {}", self.rounds)?;
+        writeln!(f, "Seat rounds played:      {}", self.seat_rounds)?;
+        writeln!(f, "Hands played:            {}", self.hands)?;
+        writeln!(f, "Win rate:                {:.4}%", self.win_rate() * 100.0)?;
+        writeln!(f, "Loss rate:               {:.4}%", self.loss_rate() * 100.0)?;
+        writeln!(f, "Push rate:               {:.4}%", self.push_rate() * 100.0)?;
+        writeln!(f, "Surrender rate:          {:.4}%", self.surrender_rate() * 100.0)?;
+        writeln!(f, "Blackjack frequency:     {:.4}%", self.blackjack_frequency() * 100.0)?;
+        writeln!(f, "EV per seat round:       {:+.4}%", self.expected_value() * 100.0)?;
+        writeln!(f, "Std dev per table round: {:.4}", self.std_dev())?;
+        write!(f, "95% CI for EV:           [{:+.4}%, {:+.4}%]", low * 100.0, high * 100.0)
+    }
+}
+
+pub fn simulate(table: &mut table::Table, seats: &mut [&mut dyn BlackjackPlayer], rounds: u64) -> Result<Statistics, table::RoundError>
+{
+    let mut statistics = Statistics::new();
+    for _ in 0..rounds
+    {
+        statistics.record(&table.play_seats(seats)?);
+    }
+    Ok(statistics)
+}
+
+pub fn worker_seed(master_seed: u64, worker: usize) -> u64
+{
+    // SplitMix64, so neighbouring workers get unrelated shoes.
+    let mut z = master_seed.wrapping_add((worker as u64 + 1).wrapping_mul(0x9E3779B97F4A7C15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+pub fn simulate_parallel<P: BlackjackPlayer>(rules: rules::TableRules, seats: usize, rounds: u64, master_seed: u64, threads: usize) -> Result<Statistics, table::RoundError>
+{
+    let threads = threads.max(1);
+    let seats = seats.clamp(1, table::MAX_SEATS);
+    let results: Vec<Result<Statistics, table::RoundError>> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads).map(|worker| {
+            let worker_rounds = rounds / threads as u64 + u64::from((worker as u64) < rounds % threads as u64);
+            scope.spawn(move || {
+                let mut table = table::Table::with_seed(rules, worker_seed(master_seed, worker));
+                let mut players: Vec<P> = (0..seats).map(|_| P::new("Bot")).collect();
+                let mut seats: Vec<&mut dyn BlackjackPlayer> = players.iter_mut().map(|player| player as &mut dyn BlackjackPlayer).collect();
+                simulate(&mut table, &mut seats, worker_rounds)
+            })
+        }).collect();
+        workers.into_iter().map(|worker| worker.join().expect("simulation worker panicked")).collect()
+    });
+
+    let mut statistics = Statistics::new();
+    for result in results
+    {
+        statistics.merge(&result?);
+    }
+    Ok(statistics)
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::hand;
+    use crate::strategy;
+
+    fn round_of(results: &[(outcome::Outcome, f64)], insurance: Option<f64>) -> table::RoundResult
+    {
+        table::RoundResult {
+            hands: results.iter().map(|(outcome, net)| table::HandResult { hand: hand::Hand::new(), outcome: *outcome, net: *net }).collect(),
+            dealer_hand: hand::Hand::new(),
+            insurance,
+        }
+    }
+
+    #[test]
+    fn test_record_rounds()
+    {
+        let mut statistics = Statistics::new();
+        statistics.record(&[round_of(&[(outcome::Outcome::PlayerBlackjack, 1.5)], None)]);
+        statistics.record(&[round_of(&[(outcome::Outcome::DealerWin, -1.0)], Some(-0.5))]);
+        statistics.record(&[round_of(&[(outcome::Outcome::PlayerWin, 2.0), (outcome::Outcome::PlayerBust, -1.0)], None)]);
+        statistics.record(&[round_of(&[(outcome::Outcome::Push, 0.0)], None)]);
+        statistics.record(&[round_of(&[(outcome::Outcome::Surrender, -0.5)], None)]);
+
+        assert_eq!(statistics.rounds, 5);
+        assert_eq!(statistics.seat_rounds, 5);
+        assert_eq!(statistics.hands, 6);
+        assert_eq!((statistics.wins, statistics.losses, statistics.pushes, statistics.surrenders), (2, 2, 1, 1));
+        assert_eq!(statistics.player_blackjacks, 1);
+        assert_eq!(statistics.win_rate(), 2.0 / 6.0);
+        assert_eq!(statistics.blackjack_frequency(), 0.2);
+        assert_eq!(statistics.total_net(), 0.5);
+        assert_eq!(statistics.expected_value(), 0.1);
+    }
+
+    #[test]
+    fn test_std_dev_and_confidence_interval()
+    {
+        let mut statistics = Statistics::new();
+        for net in [1.0, -1.0, 1.0, -1.0]
+        {
+            let outcome = match net > 0.0
+            {
+                true => outcome::Outcome::PlayerWin,
+                false => outcome::Outcome::DealerWin,
+            };
+            statistics.record(&[round_of(&[(outcome, net)], None)]);
+        }
+
+        assert_eq!(statistics.expected_value(), 0.0);
+        assert!((statistics.std_dev() - (4.0f64 / 3.0).sqrt()).abs() < 1e-12);
+        let (low, high) = statistics.confidence_interval(Z_95);
+        assert!((high - Z_95 * statistics.std_dev() / 2.0).abs() < 1e-12);
+        assert_eq!(low, -high);
+        assert_eq!(Statistics::new().std_dev(), 0.0);
+        assert_eq!(Statistics::new().expected_value(), 0.0);
+    }
+
+    #[test]
+    fn test_simulate_is_reproducible()
+    {
+        let run = || {
+            let mut table = table::Table::with_seed(rules::TableRules::default(), 77);
+            let mut bot: strategy::BasicStrategy = BlackjackPlayer::new("Bot");
+            simulate(&mut table, &mut [&mut bot], 2000).unwrap()
+        };
+        let statistics = run();
+
+        assert_eq!(statistics, run());
+        assert_eq!(statistics.rounds, 2000);
+        assert!(statistics.hands >= 2000);
+        assert_eq!(statistics.wins + statistics.losses + statistics.pushes + statistics.surrenders, statistics.hands);
+        assert!(statistics.expected_value().abs() < 0.1);
+        assert!(statistics.std_dev() > 0.9 && statistics.std_dev() < 1.4);
+    }
+
+    #[test]
+    fn test_record_counts_a_table_round_once()
+    {
+        let mut statistics = Statistics::new();
+        statistics.record(&[round_of(&[(outcome::Outcome::PlayerWin, 1.0)], None), round_of(&[(outcome::Outcome::DealerWin, -1.0)], Some(1.0))]);
+        statistics.record(&[round_of(&[(outcome::Outcome::PlayerBlackjack, 1.5)], None), round_of(&[(outcome::Outcome::PlayerWin, 1.0)], None)]);
+
+        assert_eq!(statistics.rounds, 2);
+        assert_eq!(statistics.seat_rounds, 4);
+        assert_eq!(statistics.hands, 4);
+        assert_eq!(statistics.expected_value(), 0.875);
+        assert_eq!(statistics.blackjack_frequency(), 0.25);
+        assert!((statistics.std_dev() - 0.75 * 2.0f64.sqrt()).abs() < 1e-12);
+        assert!((statistics.std_error() - 0.375).abs() < 1e-12);
+        let (low, high) = statistics.confidence_interval(Z_95);
+        assert!((high - low - 2.0 * Z_95 * 0.375).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_merge()
+    {
+        let mut first = Statistics::new();
+        let mut second = Statistics::new();
+        let mut combined = Statistics::new();
+        for (index, (outcome, net)) in [(outcome::Outcome::PlayerWin, 1.0), (outcome::Outcome::DealerBlackjack, -1.0), (outcome::Outcome::PlayerBlackjack, 1.5)].into_iter().enumerate()
+        {
+            let round = round_of(&[(outcome, net)], None);
+            match index
+            {
+                0 => first.record(&[round]),
+                _ => second.record(&[round]),
+            };
+            combined.record(&[round_of(&[(outcome, net)], None)]);
+        }
+        first.merge(&second);
+
+        assert_eq!(first, combined);
+        assert_eq!(first.dealer_blackjacks, 1);
+    }
+
+    #[test]
+    fn test_worker_seeds_are_distinct()
+    {
+        let seeds: Vec<u64> = (0..64).map(|worker| worker_seed(42, worker)).collect();
+        for (index, seed) in seeds.iter().enumerate()
+        {
+            assert!(!seeds[index + 1..].contains(seed));
+        }
+        assert_eq!(worker_seed(42, 3), seeds[3]);
+        assert_ne!(worker_seed(43, 3), seeds[3]);
+    }
+
+    #[test]
+    fn test_simulate_parallel_is_deterministic()
+    {
+        let run = |threads| simulate_parallel::<strategy::BasicStrategy>(rules::TableRules::default(), 2, 1001, 5, threads).unwrap();
+        let statistics = run(4);
+
+        assert_eq!(statistics, run(4));
+        assert_eq!(statistics.rounds, 1001);
+        assert_eq!(statistics.seat_rounds, 2002);
+        assert_eq!(run(1).rounds, 1001);
+        assert_ne!(statistics, run(3));
+
+        let mut table = table::Table::with_seed(rules::TableRules::default(), worker_seed(5, 0));
+        let mut first: strategy::BasicStrategy = BlackjackPlayer::new("Bot");
+        let mut second: strategy::BasicStrategy = BlackjackPlayer::new("Bot");
+        assert_eq!(run(1), simulate(&mut table, &mut [&mut first, &mut second], 1001).unwrap());
+    }
+}
@@ -0,0 +1,478 @@
+use crate::card;
+use crate::hand;
+use crate::player;
+use crate::player::BlackjackAction;
+use crate::player::BlackjackPlayer;
+use crate::rank;
+use crate::rules;
+use crate::table;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Play
+{
+    Hit,
+    Stand,
+    DoubleOrHit,
+    DoubleOrStand,
+}
+
+fn up_card_value(up_card: card::Card) -> u32
+{
+    match up_card.rank
+    {
+        rank::Rank::Ace => 11,
+        _ => up_card.get_point_value(),
+    }
+}
+
+fn should_surrender(hand: &hand::Hand, up: u32, rules: &rules::TableRules) -> bool
+{
+    let value = hand.value();
+    if value.is_soft()
+    {
+        return false;
+    }
+    let total = value.total();
+    let eights = value.is_pair() && hand.cards()[0].get_point_value() == 8;
+    let h17 = rules.dealer_hits_soft_17;
+
+    match rules.surrender
+    {
+        rules::SurrenderRule::NoSurrender => false,
+        rules::SurrenderRule::Early => match up
+        {
+            11 => (5..=7).contains(&total) || (12..=17).contains(&total),
+            10 => (14..=16).contains(&total),
+            9 => total == 16 && !eights,
+            _ => false,
+        },
+        rules::SurrenderRule::Late if rules.decks >= 4 => match (total, up)
+        {
+            (16, 11) if eights => h17,
+            (16, 9..=11) => !eights,
+            (15, 10) => true,
+            (15, 11) | (17, 11) => h17,
+            _ => false,
+        },
+        rules::SurrenderRule::Late => match (total, up)
+        {
+            (16, 10..=11) => !eights,
+            (16, 9) => !eights && h17 && rules.decks > 1,
+            (15, 10) => true,
+            (15, 11) | (17, 11) => h17,
+            _ => false,
+        },
+    }
+}
+
+fn should_split(pair_value: u32, up: u32, rules: &rules::TableRules) -> bool
+{
+    let das = rules.double.after_split;
+    let few_decks = rules.decks <= 2;
+
+    match pair_value
+    {
+        1 | 8 => true,
+        10 | 5 => false,
+        9 => (2..=9).contains(&up) && up != 7,
+        7 => (2..=7).contains(&up),
+        6 if das && few_decks => (2..=7).contains(&up),
+        6 if das => (2..=6).contains(&up),
+        6 => (3..=6).contains(&up),
+        4 if das && rules.decks == 1 => (4..=6).contains(&up),
+        4 if das => (5..=6).contains(&up),
+        4 => false,
+        _ if das => (2..=7).contains(&up),
+        2 if few_decks => (3..=7).contains(&up),
+        _ => (4..=7).contains(&up),
+    }
+}
+
+fn soft_play(total: u32, up: u32, rules: &rules::TableRules) -> Play
+{
+    let h17 = rules.dealer_hits_soft_17;
+
+    match total
+    {
+        20..=21 => Play::Stand,
+        19 if up == 6 && (h17 || rules.decks == 1) => Play::DoubleOrStand,
+        19 => Play::Stand,
+        18 if up == 2 && h17 => Play::DoubleOrStand,
+        18 if (3..=6).contains(&up) => Play::DoubleOrStand,
+        18 if up == 11 && rules.decks == 1 && !h17 => Play::Stand,
+        18 if up <= 8 => Play::Stand,
+        18 => Play::Hit,
+        17 if up == 2 && rules.decks == 1 => Play::DoubleOrHit,
+        17 if (3..=6).contains(&up) => Play::DoubleOrHit,
+        15..=16 if (4..=6).contains(&up) => Play::DoubleOrHit,
+        13..=14 if (5..=6).contains(&up) => Play::DoubleOrHit,
+        _ => Play::Hit,
+    }
+}
+
+fn hard_play(total: u32, up: u32, rules: &rules::TableRules) -> Play
+{
+    let few_decks = rules.decks <= 2;
+
+    match total
+    {
+        17..=21 => Play::Stand,
+        13..=16 if up <= 6 => Play::Stand,
+        12 if (4..=6).contains(&up) => Play::Stand,
+        11 if up == 11 => match rules.dealer_hits_soft_17 || few_decks
+        {
+            true => Play::DoubleOrHit,
+            false => Play::Hit,
+        },
+        11 => Play::DoubleOrHit,
+        10 if up <= 9 => Play::DoubleOrHit,
+        9 if (3..=6).contains(&up) || (up == 2 && few_decks) => Play::DoubleOrHit,
+        8 if (5..=6).contains(&up) && rules.decks == 1 => Play::DoubleOrHit,
+        _ => Play::Hit,
+    }
+}
+
+pub fn recommend(hand: &hand::Hand, up_card: card::Card, rules: &rules::TableRules, actions: &[BlackjackAction]) -> BlackjackAction
+{
+    let up = up_card_value(up_card);
+    let value = hand.value();
+    let allowed = |action: BlackjackAction| actions.contains(&action);
+
+    if allowed(BlackjackAction::Surrender) && should_surrender(hand, up, rules)
+    {
+        return BlackjackAction::Surrender;
+    }
+    if allowed(BlackjackAction::Split) && value.is_pair() && should_split(hand.cards()[0].get_point_value(), up, rules)
+    {
+        return BlackjackAction::Split;
+    }
+
+    let play = match value.is_soft()
+    {
+        true => soft_play(value.total(), up, rules),
+        false => hard_play(value.total(), up, rules),
+    };
+    let action = match play
+    {
+        Play::Hit => BlackjackAction::Hit,
+        Play::Stand => BlackjackAction::Stay,
+        Play::DoubleOrHit | Play::DoubleOrStand if allowed(BlackjackAction::Double) => BlackjackAction::Double,
+        Play::DoubleOrHit => BlackjackAction::Hit,
+        Play::DoubleOrStand => BlackjackAction::Stay,
+    };
+
+    match allowed(action)
+    {
+        true => action,
+        false => BlackjackAction::Stay,
+    }
+}
+
+fn describe(hand: &hand::Hand) -> String
+{
+    let value = hand.value();
+    match (value.is_pair(), value.is_soft())
+    {
+        (true, _) if hand.cards()[0].get_point_value() == 10 => String::from("a pair of tens"),
+        (true, _) => format!("a pair of {}s", hand.cards()[0].rank),
+        (false, true) => format!("soft {}", value.total()),
+        (false, false) => format!("hard {}", value.total()),
+    }
+}
+
+pub fn explain(hand: &hand::Hand, up_card: card::Card, action: BlackjackAction) -> String
+{
+    let reason = match action
+    {
+        BlackjackAction::Surrender => "giving up half the bet loses less than playing it out",
+        BlackjackAction::Split => "two fresh hands are worth more than this total",
+        BlackjackAction::Double => "you are the favourite here, so put more money on it",
+        BlackjackAction::Stay if hand.value().total() >= 17 => "your total is strong enough to stand",
+        BlackjackAction::Stay => "the dealer is more likely to bust than you are to improve",
+        BlackjackAction::Hit => "your total is too weak to stand against this card",
+    };
+    format!("Basic strategy says {} with {} against a dealer {}: {}.", action.to_string().to_lowercase(), describe(hand), up_card.rank, reason)
+}
+
+pub struct BasicStrategy
+{
+    name: &'static str,
+    hands: Vec<hand::Hand>,
+}
+
+impl BlackjackPlayer for BasicStrategy
+{
+    fn new(name: &'static str) -> BasicStrategy
+    {
+        BasicStrategy { name, hands: vec![hand::Hand::new()] }
+    }
+
+    fn name(&self) -> &'static str
+    {
+        self.name
+    }
+
+    fn hit_or_stay(&self, hand_index: usize, actions: &[player::BlackjackAction], view: &table::TableView) -> player::BlackjackAction
+    {
+        match view.dealer_up_card
+        {
+            Some(up_card) => recommend(&self.hands[hand_index], up_card, &view.rules, actions),
+            None => player::BlackjackAction::Stay,
+        }
+    }
+
+    fn hands(&self) -> &[hand::Hand]
+    {
+        &self.hands
+    }
+
+    fn hands_mut(&mut self) -> &mut Vec<hand::Hand>
+    {
+        &mut self.hands
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    const ALL_ACTIONS: [BlackjackAction; 5] = [BlackjackAction::Hit, BlackjackAction::Stay, BlackjackAction::Double, BlackjackAction::Split, BlackjackAction::Surrender];
+    const NO_SURRENDER: [BlackjackAction; 4] = [BlackjackAction::Hit, BlackjackAction::Stay, BlackjackAction::Double, BlackjackAction::Split];
+    const HIT_OR_STAY: [BlackjackAction; 2] = [BlackjackAction::Hit, BlackjackAction::Stay];
+
+    fn play(notation: &str, up_card: &str, rules: &rules::TableRules, actions: &[BlackjackAction]) -> BlackjackAction
+    {
+        let mut hand = hand::Hand::new();
+        for card in notation.split_whitespace()
+        {
+            hand.add_card_to_hand(card.parse().unwrap());
+        }
+        recommend(&hand, up_card.parse().unwrap(), rules, actions)
+    }
+
+    fn s17() -> rules::TableRules
+    {
+        rules::TableRules { dealer_hits_soft_17: false, decks: 6, surrender: rules::SurrenderRule::NoSurrender, ..rules::TableRules::default() }
+    }
+
+    fn h17() -> rules::TableRules
+    {
+        rules::TableRules { dealer_hits_soft_17: true, ..s17() }
+    }
+
+    #[test]
+    fn test_hard_totals()
+    {
+        assert_eq!(play("TS 7H", "AC", &s17(), &ALL_ACTIONS), BlackjackAction::Stay);
+        assert_eq!(play("TS 6H", "6C", &s17(), &ALL_ACTIONS), BlackjackAction::Stay);
+        assert_eq!(play("TS 6H", "7C", &s17(), &ALL_ACTIONS), BlackjackAction::Hit);
+        assert_eq!(play("TS 2H", "3C", &s17(), &ALL_ACTIONS), BlackjackAction::Hit);
+        assert_eq!(play("TS 2H", "4C", &s17(), &ALL_ACTIONS), BlackjackAction::Stay);
+        assert_eq!(play("6S 4H", "9C", &s17(), &ALL_ACTIONS), BlackjackAction::Double);
+        assert_eq!(play("6S 4H", "TC", &s17(), &ALL_ACTIONS), BlackjackAction::Hit);
+        assert_eq!(play("5S 4H", "2C", &s17(), &ALL_ACTIONS), BlackjackAction::Hit);
+        assert_eq!(play("5S 4H", "3C", &s17(), &ALL_ACTIONS), BlackjackAction::Double);
+        assert_eq!(play("5S 3H", "6C", &s17(), &ALL_ACTIONS), BlackjackAction::Hit);
+    }
+
+    #[test]
+    fn test_hard_totals_vary_by_rules()
+    {
+        let two_decks = rules::TableRules { decks: 2, ..s17() };
+        let one_deck = rules::TableRules { decks: 1, ..s17() };
+
+        assert_eq!(play("6S 5H", "AC", &s17(), &ALL_ACTIONS), BlackjackAction::Hit);
+        assert_eq!(play("6S 5H", "AC", &h17(), &ALL_ACTIONS), BlackjackAction::Double);
+        assert_eq!(play("6S 5H", "AC", &two_decks, &ALL_ACTIONS), BlackjackAction::Double);
+        assert_eq!(play("5S 4H", "2C", &two_decks, &ALL_ACTIONS), BlackjackAction::Double);
+        assert_eq!(play("5S 3H", "6C", &one_deck, &ALL_ACTIONS), BlackjackAction::Double);
+    }
+
+    #[test]
+    fn test_soft_totals()
+    {
+        assert_eq!(play("AS 8H", "6C", &s17(), &ALL_ACTIONS), BlackjackAction::Stay);
+        assert_eq!(play("AS 8H", "6C", &h17(), &ALL_ACTIONS), BlackjackAction::Double);
+        assert_eq!(play("AS 7H", "2C", &s17(), &ALL_ACTIONS), BlackjackAction::Stay);
+        assert_eq!(play("AS 7H", "2C", &h17(), &ALL_ACTIONS), BlackjackAction::Double);
+        assert_eq!(play("AS 7H", "5C", &s17(), &ALL_ACTIONS), BlackjackAction::Double);
+        assert_eq!(play("AS 7H", "8C", &s17(), &ALL_ACTIONS), BlackjackAction::Stay);
+        assert_eq!(play("AS 7H", "9C", &s17(), &ALL_ACTIONS), BlackjackAction::Hit);
+        assert_eq!(play("AS 6H", "3C", &s17(), &ALL_ACTIONS), BlackjackAction::Double);
+        assert_eq!(play("AS 6H", "2C", &s17(), &ALL_ACTIONS), BlackjackAction::Hit);
+        assert_eq!(play("AS 4H", "4C", &s17(), &ALL_ACTIONS), BlackjackAction::Double);
+        assert_eq!(play("AS 2H", "4C", &s17(), &ALL_ACTIONS), BlackjackAction::Hit);
+        assert_eq!(play("AS 2H", "5C", &s17(), &ALL_ACTIONS), BlackjackAction::Double);
+    }
+
+    #[test]
+    fn test_double_falls_back_when_not_allowed()
+    {
+        assert_eq!(play("AS 7H", "4C", &s17(), &HIT_OR_STAY), BlackjackAction::Stay);
+        assert_eq!(play("AS 4H 2D", "4C", &s17(), &HIT_OR_STAY), BlackjackAction::Hit);
+        assert_eq!(play("4S 5H 2D", "6C", &s17(), &HIT_OR_STAY), BlackjackAction::Hit);
+        assert_eq!(play("TS 6H", "TC", &s17(), &[BlackjackAction::Stay]), BlackjackAction::Stay);
+    }
+
+    #[test]
+    fn test_pairs()
+    {
+        assert_eq!(play("AS AH", "AC", &s17(), &ALL_ACTIONS), BlackjackAction::Split);
+        assert_eq!(play("8S 8H", "TC", &s17(), &ALL_ACTIONS), BlackjackAction::Split);
+        assert_eq!(play("TS KH", "6C", &s17(), &ALL_ACTIONS), BlackjackAction::Stay);
+        assert_eq!(play("5S 5H", "6C", &s17(), &ALL_ACTIONS), BlackjackAction::Double);
+        assert_eq!(play("9S 9H", "7C", &s17(), &ALL_ACTIONS), BlackjackAction::Stay);
+        assert_eq!(play("9S 9H", "8C", &s17(), &ALL_ACTIONS), BlackjackAction::Split);
+        assert_eq!(play("7S 7H", "8C", &s17(), &ALL_ACTIONS), BlackjackAction::Hit);
+        assert_eq!(play("8S 8H", "TC", &s17(), &[BlackjackAction::Hit, BlackjackAction::Stay]), BlackjackAction::Hit);
+        assert_eq!(play("AS AH", "6C", &s17(), &[BlackjackAction::Hit, BlackjackAction::Stay]), BlackjackAction::Hit);
+    }
+
+    #[test]
+    fn test_pairs_vary_by_double_after_split()
+    {
+        let no_das = rules::TableRules { double: rules::DoubleRules { after_split: false, ..rules::DoubleRules::default() }, ..s17() };
+
+        assert_eq!(play("6S 6H", "2C", &s17(), &ALL_ACTIONS), BlackjackAction::Split);
+        assert_eq!(play("6S 6H", "2C", &no_das, &ALL_ACTIONS), BlackjackAction::Hit);
+        assert_eq!(play("4S 4H", "5C", &s17(), &ALL_ACTIONS), BlackjackAction::Split);
+        assert_eq!(play("4S 4H", "5C", &no_das, &ALL_ACTIONS), BlackjackAction::Hit);
+        assert_eq!(play("2S 2H", "3C", &s17(), &ALL_ACTIONS), BlackjackAction::Split);
+        assert_eq!(play("2S 2H", "3C", &no_das, &ALL_ACTIONS), BlackjackAction::Hit);
+        assert_eq!(play("3S 3H", "4C", &no_das, &ALL_ACTIONS), BlackjackAction::Split);
+    }
+
+    #[test]
+    fn test_late_surrender()
+    {
+        let late_s17 = rules::TableRules { surrender: rules::SurrenderRule::Late, ..s17() };
+        let late_h17 = rules::TableRules { surrender: rules::SurrenderRule::Late, ..h17() };
+
+        assert_eq!(play("TS 6H", "TC", &late_s17, &ALL_ACTIONS), BlackjackAction::Surrender);
+        assert_eq!(play("TS 6H", "TC", &late_s17, &NO_SURRENDER), BlackjackAction::Hit);
+        assert_eq!(play("TS 6H", "9C", &late_s17, &ALL_ACTIONS), BlackjackAction::Surrender);
+        assert_eq!(play("TS 5H", "TC", &late_s17, &ALL_ACTIONS), BlackjackAction::Surrender);
+        assert_eq!(play("TS 5H", "AC", &late_s17, &ALL_ACTIONS), BlackjackAction::Hit);
+        assert_eq!(play("TS 5H", "AC", &late_h17, &ALL_ACTIONS), BlackjackAction::Surrender);
+        assert_eq!(play("TS 7H", "AC", &late_h17, &ALL_ACTIONS), BlackjackAction::Surrender);
+        assert_eq!(play("8S 8H", "TC", &late_s17, &ALL_ACTIONS), BlackjackAction::Split);
+        assert_eq!(play("8S 8H", "AC", &late_s17, &ALL_ACTIONS), BlackjackAction::Split);
+        assert_eq!(play("8S 8H", "AC", &late_h17, &ALL_ACTIONS), BlackjackAction::Surrender);
+        assert_eq!(play("AS 5H", "TC", &late_s17, &ALL_ACTIONS), BlackjackAction::Hit);
+    }
+
+    #[test]
+    fn test_early_surrender()
+    {
+        let early = rules::TableRules { surrender: rules::SurrenderRule::Early, ..s17() };
+
+        assert_eq!(play("TS 4H", "TC", &early, &ALL_ACTIONS), BlackjackAction::Surrender);
+        assert_eq!(play("8S 8H", "TC", &early, &ALL_ACTIONS), BlackjackAction::Surrender);
+        assert_eq!(play("5S 2H", "AC", &early, &ALL_ACTIONS), BlackjackAction::Surrender);
+        assert_eq!(play("TS 3H", "AC", &early, &ALL_ACTIONS), BlackjackAction::Surrender);
+        assert_eq!(play("6S 2H", "AC", &early, &ALL_ACTIONS), BlackjackAction::Hit);
+        assert_eq!(play("TS 3H", "TC", &early, &ALL_ACTIONS), BlackjackAction::Hit);
+    }
+
+    struct AlwaysStands
+    {
+        hands: Vec<hand::Hand>,
+    }
+
+    impl BlackjackPlayer for AlwaysStands
+    {
+        fn new(_: &'static str) -> AlwaysStands
+        {
+            AlwaysStands { hands: vec![hand::Hand::new()] }
+        }
+
+        fn name(&self) -> &'static str
+        {
+            "Stands"
+        }
+
+        fn hit_or_stay(&self, _: usize, _: &[player::BlackjackAction], _: &table::TableView) -> player::BlackjackAction
+        {
+            player::BlackjackAction::Stay
+        }
+
+        fn hands(&self) -> &[hand::Hand]
+        {
+            &self.hands
+        }
+
+        fn hands_mut(&mut self) -> &mut Vec<hand::Hand>
+        {
+            &mut self.hands
+        }
+    }
+
+    fn stacked_round(notation: &str) -> table::RoundResult
+    {
+        let mut table = table::Table::with_shoe(rules::TableRules::default(), crate::shoe::Shoe::stacked(crate::deck::Deck::from_notation(notation).unwrap()));
+        let mut bot: BasicStrategy = BlackjackPlayer::new("Bot");
+        table.play_round(&mut bot).unwrap()
+    }
+
+    #[test]
+    fn test_basic_strategy_seat()
+    {
+        let bot: BasicStrategy = BlackjackPlayer::new("Bot");
+        assert_eq!(bot.name(), "Bot");
+
+        let result = stacked_round("6S 5H 5C TD 9H 8C");
+        assert!(result.hands[0].hand.is_doubled());
+        assert_eq!(result.hands[0].net, 2.0);
+
+        let result = stacked_round("8S TH 8C 7D TS 9C");
+        assert_eq!(result.hands.len(), 2);
+        assert_eq!(result.hands[0].hand.cards(), &["8S".parse().unwrap(), "TS".parse().unwrap()]);
+        assert_eq!(result.hands[0].net + result.hands[1].net, 1.0);
+
+        let result = stacked_round("TS 6H 3C TD 8S");
+        assert_eq!(result.hands[0].hand.get_num_cards(), 2);
+        assert_eq!(result.hands[0].outcome, crate::outcome::Outcome::DealerBust);
+
+        let result = stacked_round("TS 7H 2C TD 9S 5C");
+        assert_eq!(result.hands[0].hand.get_num_cards(), 3);
+        assert_eq!(result.hands[0].outcome, crate::outcome::Outcome::PlayerWin);
+    }
+
+    #[test]
+    fn test_basic_strategy_beats_always_standing()
+    {
+        let expected_value = |bot: &mut dyn BlackjackPlayer| {
+            let mut table = table::Table::with_seed(rules::TableRules::default(), 17);
+            crate::sim::simulate(&mut table, &mut [bot], 5000).unwrap().expected_value()
+        };
+        let mut basic: BasicStrategy = BlackjackPlayer::new("Bot");
+        let mut stands: AlwaysStands = BlackjackPlayer::new("Stands");
+        let basic = expected_value(&mut basic);
+        let stands = expected_value(&mut stands);
+
+        assert!(basic > stands + 0.08, "basic strategy {} vs always standing {}", basic, stands);
+        assert!(basic.abs() < 0.05, "{}", basic);
+    }
+
+    #[test]
+    fn test_explain()
+    {
+        let mut hand = hand::Hand::new();
+        hand.add_card_to_hand("TS".parse().unwrap());
+        hand.add_card_to_hand("6H".parse().unwrap());
+        assert_eq!(explain(&hand, "TC".parse().unwrap(), BlackjackAction::Surrender),
+            "Basic strategy says surrender with hard 16 against a dealer 10: giving up half the bet loses less than playing it out.");
+        assert!(explain(&hand, "5C".parse().unwrap(), BlackjackAction::Stay).contains("more likely to bust"));
+
+        let mut hand = hand::Hand::new();
+        hand.add_card_to_hand("AS".parse().unwrap());
+        hand.add_card_to_hand("7H".parse().unwrap());
+        assert!(explain(&hand, "AC".parse().unwrap(), BlackjackAction::Hit).starts_with("Basic strategy says hit with soft 18 against a dealer Ace"));
+
+        let mut hand = hand::Hand::new();
+        hand.add_card_to_hand("8S".parse().unwrap());
+        hand.add_card_to_hand("8H".parse().unwrap());
+        assert!(explain(&hand, "TC".parse().unwrap(), BlackjackAction::Split).contains("with a pair of 8s"));
+    }
+}
@@ -1,4 +1,5 @@
 use std::fmt;
+use std::str;
 use std::convert;
 use std::error;
 
@@ -15,6 +16,19 @@ impl fmt::Display for InvalidIntToSuit
 
 impl error::Error for InvalidIntToSuit {}
 
+#[derive(Debug, Eq, PartialEq)]
+pub struct InvalidSuitNotation;
+
+impl fmt::Display for InvalidSuitNotation
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        write!(f, "invalid suit; expected one of S, H, D, C or ♠, ♥, ♦, ♣")
+    }
+}
+
+impl error::Error for InvalidSuitNotation {}
+
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum Suit
 {
@@ -28,6 +42,10 @@ impl fmt::Display for Suit
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
     {
+        if f.alternate()
+        {
+            return write!(f, "{}", self.symbol());
+        }
         match &self
         {
             Suit::Diamonds => write!(f, "Diamonds"),
@@ -38,6 +56,37 @@ impl fmt::Display for Suit
     }
 }
 
+impl str::FromStr for Suit
+{
+    type Err = InvalidSuitNotation;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err>
+    {
+        match s.trim().to_uppercase().as_str()
+        {
+            "D" | "♦" | "♢" | "DIAMONDS" => Ok(Suit::Diamonds),
+            "C" | "♣" | "♧" | "CLUBS" => Ok(Suit::Clubs),
+            "H" | "♥" | "♡" | "HEARTS" => Ok(Suit::Hearts),
+            "S" | "♠" | "♤" | "SPADES" => Ok(Suit::Spades),
+            _ => Err(InvalidSuitNotation)
+        }
+    }
+}
+
+impl Suit
+{
+    pub fn symbol(&self) -> &'static str
+    {
+        match &self
+        {
+            Suit::Diamonds => "♦",
+            Suit::Clubs => "♣",
+            Suit::Hearts => "♥",
+            Suit::Spades => "♠",
+        }
+    }
+}
+
 impl convert::TryFrom<u32> for Suit
 {
     type Error = InvalidIntToSuit;
@@ -84,12 +133,33 @@ mod tests
         for n in 0..10
         {
             let try_suit_from_u32 = Suit::try_from(n);
-            let expected_okay = match Some(n)
-            {
-                Some(x) if x >= Suit::Diamonds as u32 && x <= Suit::Spades as u32 => true,
-                _ => false,
-            };
+            let expected_okay = matches!(Some(n), Some(x) if x >= Suit::Diamonds as u32 && x <= Suit::Spades as u32);
             assert_eq!(try_suit_from_u32.is_ok(), expected_okay);
         }
     }
+
+    #[test]
+    fn test_format_suit_compact()
+    {
+        assert_eq!(format!("{:#}", Suit::Diamonds), "♦");
+        assert_eq!(format!("{:#}", Suit::Clubs), "♣");
+        assert_eq!(format!("{:#}", Suit::Hearts), "♥");
+        assert_eq!(format!("{:#}", Suit::Spades), "♠");
+    }
+
+    #[test]
+    fn test_suit_from_str()
+    {
+        assert_eq!("D".parse::<Suit>(), Ok(Suit::Diamonds));
+        assert_eq!("c".parse::<Suit>(), Ok(Suit::Clubs));
+        assert_eq!("h".parse::<Suit>(), Ok(Suit::Hearts));
+        assert_eq!("S".parse::<Suit>(), Ok(Suit::Spades));
+        assert_eq!("♦".parse::<Suit>(), Ok(Suit::Diamonds));
+        assert_eq!("♣".parse::<Suit>(), Ok(Suit::Clubs));
+        assert_eq!("♡".parse::<Suit>(), Ok(Suit::Hearts));
+        assert_eq!("♠".parse::<Suit>(), Ok(Suit::Spades));
+        assert_eq!("spades".parse::<Suit>(), Ok(Suit::Spades));
+        assert_eq!("X".parse::<Suit>(), Err(InvalidSuitNotation));
+        assert_eq!("".parse::<Suit>(), Err(InvalidSuitNotation));
+    }
 }
@@ -0,0 +1,1302 @@
+use std::fmt;
+use std::error;
+
+use crate::card;
+use crate::count;
+use crate::deck;
+use crate::hand;
+use crate::outcome;
+use crate::player;
+use crate::player::BlackjackPlayer;
+use crate::rank;
+use crate::rules;
+use crate::shoe;
+
+pub const MAX_SEATS: usize = 7;
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum RoundError
+{
+    EmptyDeck,
+    TooManySeats(usize),
+}
+
+impl fmt::Display for RoundError
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match &self
+        {
+            RoundError::EmptyDeck => write!(f, "{}", deck::EmptyDeck),
+            RoundError::TooManySeats(seats) => write!(f, "{} seats requested but the table only has {}", seats, MAX_SEATS),
+        }
+    }
+}
+
+impl error::Error for RoundError {}
+
+impl From<deck::EmptyDeck> for RoundError
+{
+    fn from(_: deck::EmptyDeck) -> Self
+    {
+        RoundError::EmptyDeck
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct TableView<'a>
+{
+    pub rules: rules::TableRules,
+    pub dealer_up_card: Option<card::Card>,
+    pub hands: &'a [hand::Hand],
+    pub other_seats: &'a [Vec<card::Card>],
+    pub cards_remaining: usize,
+    pub decks_remaining: f64,
+    pub running_count: f64,
+    pub true_count: f64,
+}
+
+impl TableView<'_>
+{
+    pub fn visible_cards(&self) -> impl Iterator<Item = &card::Card>
+    {
+        self.dealer_up_card.iter()
+            .chain(self.hands.iter().flat_map(|hand| hand.cards().iter()))
+            .chain(self.other_seats.iter().flatten())
+    }
+}
+
+#[derive(Debug)]
+pub struct HandResult
+{
+    pub hand: hand::Hand,
+    pub outcome: outcome::Outcome,
+    pub net: f64,
+}
+
+#[derive(Debug)]
+pub struct RoundResult
+{
+    pub hands: Vec<HandResult>,
+    pub dealer_hand: hand::Hand,
+    pub insurance: Option<f64>,
+}
+
+pub struct Table
+{
+    shoe: shoe::Shoe,
+    dealer: player::Dealer,
+    rules: rules::TableRules,
+    counter: count::Counter,
+    round_cards: Vec<card::Card>,
+}
+
+impl Default for Table
+{
+    fn default() -> Self
+    {
+        Table::new()
+    }
+}
+
+impl Table
+{
+    pub fn new() -> Table
+    {
+        Table::with_rules(rules::TableRules::default())
+    }
+
+    pub fn with_rules(rules: rules::TableRules) -> Table
+    {
+        Table::with_shoe(rules, shoe::Shoe::new(rules.decks, shoe::DEFAULT_PENETRATION, shoe::DEFAULT_BURN_CARDS))
+    }
+
+    pub fn with_seed(rules: rules::TableRules, seed: u64) -> Table
+    {
+        Table::with_shoe(rules, shoe::Shoe::with_seed(rules.decks, shoe::DEFAULT_PENETRATION, shoe::DEFAULT_BURN_CARDS, seed))
+    }
+
+    pub fn with_shoe(rules: rules::TableRules, shoe: shoe::Shoe) -> Table
+    {
+        let counter = count::Counter::with_system(Box::new(count::TagTable::hi_lo()), shoe.num_decks());
+        Table { shoe, dealer: player::Dealer::with_rules(&rules), rules, counter, round_cards: vec![] }
+    }
+
+    pub fn shoe(&self) -> &shoe::Shoe
+    {
+        &self.shoe
+    }
+
+    pub fn rules(&self) -> &rules::TableRules
+    {
+        &self.rules
+    }
+
+    pub fn dealer(&self) -> &player::Dealer
+    {
+        &self.dealer
+    }
+
+    pub fn counter(&self) -> &count::Counter
+    {
+        &self.counter
+    }
+
+    pub fn set_counting_system(&mut self, system: Box<dyn count::CountingSystem>)
+    {
+        self.counter = count::Counter::with_system(system, self.shoe.num_decks());
+    }
+
+    pub fn true_count(&self) -> f64
+    {
+        self.counter.true_count(self.shoe.decks_remaining())
+    }
+
+    pub fn play_round<P: BlackjackPlayer>(&mut self, player: &mut P) -> Result<RoundResult, RoundError>
+    {
+        self.play_seats(&mut [player as &mut dyn BlackjackPlayer]).map(|mut results| results.remove(0))
+    }
+
+    pub fn play_seats(&mut self, seats: &mut [&mut dyn BlackjackPlayer]) -> Result<Vec<RoundResult>, RoundError>
+    {
+        if seats.len() > MAX_SEATS
+        {
+            return Err(RoundError::TooManySeats(seats.len()));
+        }
+        if seats.is_empty()
+        {
+            return Ok(vec![]);
+        }
+        if self.shoe.needs_shuffle()
+        {
+            self.shoe.shuffle();
+            self.counter.reset();
+        }
+        self.round_cards.clear();
+
+        let results = self.deal_and_play(seats).map(|insured| seats.iter().zip(insured).map(|(seat, insured)| RoundResult {
+            hands: seat.hands().iter().map(|hand| self.hand_result(hand)).collect(),
+            dealer_hand: self.dealer.hand().clone(),
+            insurance: insured.then(|| self.settle_insurance(seat.blackjack_hand())),
+        }).collect());
+
+        if let Some(hole_card) = self.dealer.hand().cards().get(1)
+        {
+            self.counter.observe(hole_card);
+        }
+        for seat in seats.iter_mut()
+        {
+            self.shoe.collect_played_cards(seat.discard_hand());
+        }
+        self.shoe.collect_played_cards(self.dealer.discard_hand());
+
+        results.map_err(RoundError::from)
+    }
+
+    fn deal_card(&mut self) -> Result<card::Card, deck::EmptyDeck>
+    {
+        let card = self.deal_hole_card()?;
+        self.counter.observe(&card);
+        self.round_cards.push(card);
+        Ok(card)
+    }
+
+    fn deal_hole_card(&mut self) -> Result<card::Card, deck::EmptyDeck>
+    {
+        match self.shoe.deal_card()
+        {
+            Ok(card) => Ok(card),
+            Err(_) => {
+                // The cards already on the table are not in the reshuffled discards either.
+                self.shoe.reshuffle_discards();
+                self.counter.reset();
+                for card in self.round_cards.iter()
+                {
+                    self.counter.observe(card);
+                }
+                self.shoe.deal_card()
+            }
+        }
+    }
+
+    fn deal_and_play(&mut self, seats: &mut [&mut dyn BlackjackPlayer]) -> Result<Vec<bool>, deck::EmptyDeck>
+    {
+        for hole_card in [false, true]
+        {
+            for seat in seats.iter_mut()
+            {
+                seat.add_card_to_hand(self.deal_card()?);
+            }
+            let card = match hole_card
+            {
+                true => self.deal_hole_card()?,
+                false => self.deal_card()?,
+            };
+            self.dealer.add_card_to_hand(card);
+        }
+
+        let insured = (0..seats.len()).map(|seat_index| self.offer_insurance(&*seats[seat_index], &Table::other_seats(seats, seat_index))).collect();
+        self.play_hands(seats)?;
+        Ok(insured)
+    }
+
+    fn other_seats(seats: &[&mut dyn BlackjackPlayer], seat_index: usize) -> Vec<Vec<card::Card>>
+    {
+        seats.iter().enumerate().filter(|(index, _)| *index != seat_index)
+            .map(|(_, seat)| seat.hands().iter().flat_map(|hand| hand.cards().iter().copied()).collect())
+            .collect()
+    }
+
+    fn view<'a>(&self, hands: &'a [hand::Hand], other_seats: &'a [Vec<card::Card>]) -> TableView<'a>
+    {
+        TableView {
+            rules: self.rules,
+            dealer_up_card: self.dealer.up_card(),
+            hands,
+            other_seats,
+            cards_remaining: self.shoe.cards_remaining(),
+            decks_remaining: self.shoe.decks_remaining(),
+            running_count: self.counter.running_count(),
+            true_count: self.true_count(),
+        }
+    }
+
+    fn offer_insurance(&self, player: &dyn BlackjackPlayer, other_seats: &[Vec<card::Card>]) -> bool
+    {
+        match self.dealer.up_card()
+        {
+            Some(card) if card.rank == rank::Rank::Ace => player.take_insurance(player.blackjack_hand(), &self.view(player.hands(), other_seats)),
+            _ => false,
+        }
+    }
+
+    fn dealer_peeks(&self) -> bool
+    {
+        if !self.rules.dealer_peeks
+        {
+            return false;
+        }
+        match self.dealer.up_card()
+        {
+            Some(card) => card.rank == rank::Rank::Ace || card.get_point_value() == 10,
+            None => false,
+        }
+    }
+
+    fn play_hands(&mut self, seats: &mut [&mut dyn BlackjackPlayer]) -> Result<(), deck::EmptyDeck>
+    {
+        let mut first_actions = vec![None; seats.len()];
+        if self.rules.surrender == rules::SurrenderRule::Early
+        {
+            for seat_index in 0..seats.len()
+            {
+                if seats[seat_index].blackjack_hand()
+                {
+                    continue;
+                }
+                let other_seats = Table::other_seats(seats, seat_index);
+                let seat = &mut *seats[seat_index];
+                let actions = self.available_actions(seat.hand(), 1);
+                let first_action = Some(seat.hit_or_stay(0, &actions, &self.view(seat.hands(), &other_seats))).filter(|action| actions.contains(action));
+                if first_action == Some(player::BlackjackAction::Surrender)
+                {
+                    seat.hands_mut()[0].surrender();
+                }
+                first_actions[seat_index] = first_action;
+            }
+        }
+        if self.dealer_peeks() && self.dealer.blackjack_hand()
+        {
+            return Ok(());
+        }
+
+        for (seat_index, mut first_action) in first_actions.into_iter().enumerate()
+        {
+            if seats[seat_index].blackjack_hand() || seats[seat_index].hand().is_surrendered()
+            {
+                continue;
+            }
+            let other_seats = Table::other_seats(seats, seat_index);
+            let mut hand_index = 0;
+            while hand_index < seats[seat_index].hands().len()
+            {
+                self.play_hand(&mut *seats[seat_index], hand_index, first_action.take(), &other_seats)?;
+                hand_index += 1;
+            }
+        }
+        if seats.iter().all(|seat| seat.blackjack_hand() || seat.hands().iter().all(|hand| hand.value().is_bust() || hand.is_surrendered()))
+        {
+            return Ok(());
+        }
+
+        while self.dealer.next_action() == player::BlackjackAction::Hit
+        {
+            let card = self.deal_card()?;
+            self.dealer.add_card_to_hand(card);
+        }
+        Ok(())
+    }
+
+    fn play_hand(&mut self, player: &mut dyn BlackjackPlayer, hand_index: usize, mut first_action: Option<player::BlackjackAction>, other_seats: &[Vec<card::Card>]) -> Result<(), deck::EmptyDeck>
+    {
+        loop
+        {
+            if player.hands()[hand_index].get_num_cards() == 1
+            {
+                player.hands_mut()[hand_index].add_card_to_hand(self.deal_card()?);
+            }
+
+            let hand = &player.hands()[hand_index];
+            let actions = self.available_actions(hand, player.hands().len());
+            if hand.get_point_value() >= 21 || actions.len() == 1
+            {
+                return Ok(());
+            }
+
+            match first_action.take().unwrap_or_else(|| player.hit_or_stay(hand_index, &actions, &self.view(player.hands(), other_seats)))
+            {
+                action if !actions.contains(&action) => return Ok(()),
+                player::BlackjackAction::Hit => player.hands_mut()[hand_index].add_card_to_hand(self.deal_card()?),
+                player::BlackjackAction::Stay => return Ok(()),
+                player::BlackjackAction::Double => {
+                    player.hands_mut()[hand_index].double_down(self.deal_card()?);
+                    return Ok(());
+                }
+                player::BlackjackAction::Split => player.split_hand(hand_index),
+                player::BlackjackAction::Surrender => {
+                    player.hands_mut()[hand_index].surrender();
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    fn available_actions(&self, hand: &hand::Hand, num_hands: usize) -> Vec<player::BlackjackAction>
+    {
+        let split_aces = hand.is_split() && hand.cards()[0].rank == rank::Rank::Ace;
+        let mut actions = vec![];
+
+        if !split_aces || self.rules.split.hit_split_aces
+        {
+            actions.push(player::BlackjackAction::Hit);
+        }
+        actions.push(player::BlackjackAction::Stay);
+        if hand.get_num_cards() == 2 && self.rules.double.allows_total(hand.get_point_value())
+            && (!hand.is_split() || self.rules.double.after_split) && (!split_aces || self.rules.split.hit_split_aces)
+        {
+            actions.push(player::BlackjackAction::Double);
+        }
+        if hand.value().is_pair() && (num_hands as u32) < self.rules.split.max_hands && (!split_aces || self.rules.split.resplit_aces)
+        {
+            actions.push(player::BlackjackAction::Split);
+        }
+        if hand.get_num_cards() == 2 && !hand.is_split() && num_hands == 1 && self.rules.surrender != rules::SurrenderRule::NoSurrender
+        {
+            actions.push(player::BlackjackAction::Surrender);
+        }
+        actions
+    }
+
+    fn hand_result(&self, hand: &hand::Hand) -> HandResult
+    {
+        let outcome = self.settle_hand(hand);
+        let wager = match hand.is_doubled()
+        {
+            true => 2.0,
+            false => 1.0,
+        };
+        HandResult { hand: hand.clone(), outcome, net: outcome.payout(&self.rules) * wager }
+    }
+
+    fn settle_insurance(&self, even_money: bool) -> f64
+    {
+        match (self.dealer.blackjack_hand(), even_money)
+        {
+            (true, _) => 1.0,
+            // Even money pays the blackjack 1:1, so hand back whatever the payout gave above that.
+            (false, true) => 1.0 - self.rules.blackjack_payout.ratio(),
+            (false, false) => -0.5,
+        }
+    }
+
+    fn settle_hand(&self, hand: &hand::Hand) -> outcome::Outcome
+    {
+        let outcome = outcome::settle(hand, self.dealer.hand());
+        let split_blackjack = hand.is_split() && hand.get_num_cards() == 2 && hand.value().total() == 21;
+
+        match outcome
+        {
+            // Without a peek a late surrender is only honoured once the dealer is known not to have blackjack.
+            outcome::Outcome::Surrender if self.rules.surrender == rules::SurrenderRule::Late && !self.rules.dealer_peeks && self.dealer.blackjack_hand() => outcome::Outcome::DealerBlackjack,
+            outcome::Outcome::DealerBlackjack if split_blackjack && self.rules.split.blackjack_after_split => outcome::Outcome::Push,
+            outcome::Outcome::DealerBlackjack => outcome,
+            _ if split_blackjack && self.rules.split.blackjack_after_split => outcome::Outcome::PlayerBlackjack,
+            _ => outcome,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::card;
+    use crate::suit;
+
+    struct TestPlayer
+    {
+        hands: Vec<hand::Hand>,
+        prefers: player::BlackjackAction,
+        insures: bool,
+    }
+
+    impl BlackjackPlayer for TestPlayer
+    {
+        fn new(_: &'static str) -> TestPlayer
+        {
+            TestPlayer { hands: vec![hand::Hand::new()], prefers: player::BlackjackAction::Stay, insures: false }
+        }
+
+        fn name(&self) -> &'static str
+        {
+            "Test"
+        }
+
+        fn hit_or_stay(&self, _: usize, actions: &[player::BlackjackAction], _: &TableView) -> player::BlackjackAction
+        {
+            match actions.contains(&self.prefers)
+            {
+                true => self.prefers,
+                false => player::BlackjackAction::Stay,
+            }
+        }
+
+        fn hands(&self) -> &[hand::Hand]
+        {
+            &self.hands
+        }
+
+        fn take_insurance(&self, _: bool, _: &TableView) -> bool
+        {
+            self.insures
+        }
+
+        fn hands_mut(&mut self) -> &mut Vec<hand::Hand>
+        {
+            &mut self.hands
+        }
+    }
+
+    fn hand_of(ranks: &[rank::Rank]) -> hand::Hand
+    {
+        let mut hand = hand::Hand::new();
+        for rank in ranks
+        {
+            hand.add_card_to_hand(card::Card { rank: *rank, suit: suit::Suit::Clubs });
+        }
+        hand
+    }
+
+    type SeenView = (Option<card::Card>, Vec<card::Card>, usize, f64);
+
+    struct Observer
+    {
+        hands: Vec<hand::Hand>,
+        views: std::cell::RefCell<Vec<SeenView>>,
+    }
+
+    impl BlackjackPlayer for Observer
+    {
+        fn new(_: &'static str) -> Observer
+        {
+            Observer { hands: vec![hand::Hand::new()], views: std::cell::RefCell::new(vec![]) }
+        }
+
+        fn name(&self) -> &'static str
+        {
+            "Observer"
+        }
+
+        fn hit_or_stay(&self, _: usize, _: &[player::BlackjackAction], view: &TableView) -> player::BlackjackAction
+        {
+            self.views.borrow_mut().push((view.dealer_up_card, view.visible_cards().copied().collect(), view.cards_remaining, view.running_count));
+            player::BlackjackAction::Stay
+        }
+
+        fn hands(&self) -> &[hand::Hand]
+        {
+            &self.hands
+        }
+
+        fn hands_mut(&mut self) -> &mut Vec<hand::Hand>
+        {
+            &mut self.hands
+        }
+    }
+
+    fn cards_of(notation: &str) -> Vec<card::Card>
+    {
+        notation.split_whitespace().map(|card| card.parse().unwrap()).collect()
+    }
+
+    fn stacked_table(rules: rules::TableRules, notation: &str) -> Table
+    {
+        Table::with_shoe(rules, shoe::Shoe::stacked(deck::Deck::from_notation(notation).unwrap()))
+    }
+
+    #[test]
+    fn test_play_round_returns_hands_and_clears_table()
+    {
+        let mut table = Table::new();
+        let mut player: TestPlayer = BlackjackPlayer::new("Stays");
+
+        for _ in 0..50
+        {
+            let result = table.play_round(&mut player).unwrap();
+
+            assert_eq!(result.hands.len(), 1);
+            assert_eq!(result.hands[0].hand.get_num_cards(), 2);
+            assert!(result.dealer_hand.get_num_cards() >= 2);
+            assert_eq!(player.hands().len(), 1);
+            assert_eq!(player.get_num_cards(), 0);
+            assert_eq!(table.dealer().get_num_cards(), 0);
+        }
+    }
+
+    #[test]
+    fn test_play_round_shuffles_at_cut_card()
+    {
+        let mut table = Table::with_shoe(rules::TableRules::default(), shoe::Shoe::new(1, 0.5, 1));
+        let mut player: TestPlayer = BlackjackPlayer::new("Stays");
+
+        assert!(table.shoe().needs_shuffle());
+        table.play_round(&mut player).unwrap();
+        for _ in 0..100
+        {
+            let needed_shuffle = table.shoe().needs_shuffle();
+            let cards_before = table.shoe().cards_remaining();
+            let result = table.play_round(&mut player).unwrap();
+            let cards_dealt = result.hands[0].hand.get_num_cards() + result.dealer_hand.get_num_cards();
+
+            match needed_shuffle
+            {
+                true => assert_eq!(table.shoe().cards_remaining(), 51 - cards_dealt as usize),
+                false => assert_eq!(table.shoe().cards_remaining(), cards_before - cards_dealt as usize),
+            }
+        }
+    }
+
+    #[test]
+    fn test_play_round_outcome_matches_hands()
+    {
+        let mut table = Table::new();
+        let mut player: TestPlayer = BlackjackPlayer::new("Stays");
+
+        for _ in 0..200
+        {
+            let result = table.play_round(&mut player).unwrap();
+            let player_hand = &result.hands[0].hand;
+            let dealer_blackjack = result.dealer_hand.get_point_value() == 21 && result.dealer_hand.get_num_cards() == 2;
+
+            assert_eq!(result.hands[0].outcome, outcome::settle(player_hand, &result.dealer_hand));
+            if player_hand.get_point_value() == 21 || dealer_blackjack
+            {
+                assert_eq!(result.dealer_hand.get_num_cards(), 2);
+            }
+            else if result.dealer_hand.get_point_value() < 17
+            {
+                panic!("dealer stood on {}", result.dealer_hand.get_point_value());
+            }
+        }
+    }
+
+    #[test]
+    fn test_play_round_splits_up_to_max_hands()
+    {
+        let mut table = Table::new();
+        let mut player: TestPlayer = BlackjackPlayer::new("Splits");
+        player.prefers = player::BlackjackAction::Split;
+        table.rules.split = rules::SplitRules { max_hands: 3, ..rules::SplitRules::default() };
+
+        let mut split_rounds = 0;
+        for _ in 0..500
+        {
+            let result = table.play_round(&mut player).unwrap();
+
+            assert!(result.hands.len() <= 3);
+            if result.hands.len() > 1
+            {
+                split_rounds += 1;
+            }
+            for hand_result in result.hands.iter()
+            {
+                assert!(hand_result.hand.get_num_cards() >= 2);
+                assert_eq!(hand_result.hand.is_split(), result.hands.len() > 1);
+                if hand_result.hand.is_split() && hand_result.hand.cards()[0].rank == rank::Rank::Ace
+                {
+                    assert_eq!(hand_result.hand.get_num_cards(), 2);
+                    assert_ne!(hand_result.outcome, outcome::Outcome::PlayerBlackjack);
+                }
+            }
+            assert_eq!(player.hands().len(), 1);
+        }
+        assert!(split_rounds > 0);
+    }
+
+    #[test]
+    fn test_available_actions()
+    {
+        let mut table = Table::new();
+        table.rules.double = rules::DoubleRules { on: rules::DoubleOn::Never, ..rules::DoubleRules::default() };
+        table.rules.surrender = rules::SurrenderRule::NoSurrender;
+        let hit_or_stay = vec![player::BlackjackAction::Hit, player::BlackjackAction::Stay];
+        let with_split = vec![player::BlackjackAction::Hit, player::BlackjackAction::Stay, player::BlackjackAction::Split];
+
+        assert_eq!(table.available_actions(&hand_of(&[rank::Rank::Ten, rank::Rank::Six]), 1), hit_or_stay);
+        assert_eq!(table.available_actions(&hand_of(&[rank::Rank::Eight, rank::Rank::Eight]), 1), with_split);
+        assert_eq!(table.available_actions(&hand_of(&[rank::Rank::Eight, rank::Rank::Eight]), 4), hit_or_stay);
+    }
+
+    #[test]
+    fn test_available_actions_double()
+    {
+        let mut table = Table::new();
+        table.rules.surrender = rules::SurrenderRule::NoSurrender;
+        let with_double = vec![player::BlackjackAction::Hit, player::BlackjackAction::Stay, player::BlackjackAction::Double];
+        let hit_or_stay = vec![player::BlackjackAction::Hit, player::BlackjackAction::Stay];
+
+        assert_eq!(table.available_actions(&hand_of(&[rank::Rank::Ten, rank::Rank::Six]), 1), with_double);
+        assert_eq!(table.available_actions(&hand_of(&[rank::Rank::Ten, rank::Rank::Two, rank::Rank::Two]), 1), hit_or_stay);
+
+        table.rules.double = rules::DoubleRules { on: rules::DoubleOn::NineToEleven, ..rules::DoubleRules::default() };
+        assert_eq!(table.available_actions(&hand_of(&[rank::Rank::Ten, rank::Rank::Six]), 1), hit_or_stay);
+        assert_eq!(table.available_actions(&hand_of(&[rank::Rank::Five, rank::Rank::Four]), 1), with_double);
+        assert_eq!(table.available_actions(&hand_of(&[rank::Rank::Six, rank::Rank::Five]), 1), with_double);
+
+        table.rules.double = rules::DoubleRules { on: rules::DoubleOn::TenToEleven, ..rules::DoubleRules::default() };
+        assert_eq!(table.available_actions(&hand_of(&[rank::Rank::Five, rank::Rank::Four]), 1), hit_or_stay);
+        assert_eq!(table.available_actions(&hand_of(&[rank::Rank::Six, rank::Rank::Four]), 1), with_double);
+
+        let mut split_hand = hand_of(&[rank::Rank::Five, rank::Rank::Five]);
+        split_hand.split();
+        split_hand.add_card_to_hand(card::Card { rank: rank::Rank::Six, suit: suit::Suit::Hearts });
+        assert_eq!(table.available_actions(&split_hand, 2), with_double);
+        table.rules.double = rules::DoubleRules { on: rules::DoubleOn::AnyTwoCards, after_split: false };
+        assert_eq!(table.available_actions(&split_hand, 2), hit_or_stay);
+    }
+
+    #[test]
+    fn test_play_round_double_takes_one_card()
+    {
+        let mut table = Table::new();
+        let mut player: TestPlayer = BlackjackPlayer::new("Doubles");
+        player.prefers = player::BlackjackAction::Double;
+
+        let mut doubled_rounds = 0;
+        for _ in 0..200
+        {
+            let result = table.play_round(&mut player).unwrap();
+            let hand = &result.hands[0].hand;
+
+            if hand.is_doubled()
+            {
+                doubled_rounds += 1;
+                assert_eq!(hand.get_num_cards(), 3);
+            }
+            else
+            {
+                assert_eq!(hand.get_num_cards(), 2);
+            }
+        }
+        assert!(doubled_rounds > 0);
+    }
+
+    #[test]
+    fn test_available_actions_split_aces()
+    {
+        let mut table = Table::new();
+        let mut aces = hand_of(&[rank::Rank::Ace, rank::Rank::Ace]);
+        aces.split();
+        aces.add_card_to_hand(card::Card { rank: rank::Rank::Ace, suit: suit::Suit::Hearts });
+
+        assert_eq!(table.available_actions(&aces, 2), vec![player::BlackjackAction::Stay]);
+
+        table.rules.split = rules::SplitRules { resplit_aces: true, ..rules::SplitRules::default() };
+        assert_eq!(table.available_actions(&aces, 2), vec![player::BlackjackAction::Stay, player::BlackjackAction::Split]);
+
+        table.rules.split = rules::SplitRules { resplit_aces: true, hit_split_aces: true, ..rules::SplitRules::default() };
+        assert_eq!(table.available_actions(&aces, 4), vec![player::BlackjackAction::Hit, player::BlackjackAction::Stay, player::BlackjackAction::Double]);
+    }
+
+    #[test]
+    fn test_settle_hand_blackjack_after_split()
+    {
+        let mut table = Table::new();
+        table.dealer.add_card_to_hand(card::Card { rank: rank::Rank::Ten, suit: suit::Suit::Hearts });
+        table.dealer.add_card_to_hand(card::Card { rank: rank::Rank::Nine, suit: suit::Suit::Hearts });
+        let mut aces = hand_of(&[rank::Rank::Ace, rank::Rank::Ace]);
+        aces.split();
+        aces.add_card_to_hand(card::Card { rank: rank::Rank::King, suit: suit::Suit::Hearts });
+
+        assert_eq!(table.settle_hand(&aces), outcome::Outcome::PlayerWin);
+
+        table.rules.split = rules::SplitRules { blackjack_after_split: true, ..rules::SplitRules::default() };
+        assert_eq!(table.settle_hand(&aces), outcome::Outcome::PlayerBlackjack);
+    }
+
+    #[test]
+    fn test_available_actions_surrender()
+    {
+        let mut table = Table::new();
+        table.rules.surrender = rules::SurrenderRule::Late;
+        table.rules.double = rules::DoubleRules { on: rules::DoubleOn::Never, ..rules::DoubleRules::default() };
+        let with_surrender = vec![player::BlackjackAction::Hit, player::BlackjackAction::Stay, player::BlackjackAction::Surrender];
+        let hit_or_stay = vec![player::BlackjackAction::Hit, player::BlackjackAction::Stay];
+
+        assert_eq!(table.available_actions(&hand_of(&[rank::Rank::Ten, rank::Rank::Six]), 1), with_surrender);
+        assert_eq!(table.available_actions(&hand_of(&[rank::Rank::Ten, rank::Rank::Two, rank::Rank::Four]), 1), hit_or_stay);
+
+        let mut split_hand = hand_of(&[rank::Rank::Eight, rank::Rank::Eight]);
+        split_hand.split();
+        split_hand.add_card_to_hand(card::Card { rank: rank::Rank::Eight, suit: suit::Suit::Hearts });
+        table.rules.split = rules::SplitRules::no_splits();
+        assert_eq!(table.available_actions(&split_hand, 2), hit_or_stay);
+
+        table.rules.surrender = rules::SurrenderRule::NoSurrender;
+        assert_eq!(table.available_actions(&hand_of(&[rank::Rank::Ten, rank::Rank::Six]), 1), hit_or_stay);
+    }
+
+    #[test]
+    fn test_play_round_late_surrender()
+    {
+        let mut table = Table::new();
+        let mut player: TestPlayer = BlackjackPlayer::new("Surrenders");
+        player.prefers = player::BlackjackAction::Surrender;
+        table.rules.surrender = rules::SurrenderRule::Late;
+
+        for _ in 0..200
+        {
+            let result = table.play_round(&mut player).unwrap();
+            let hand_result = &result.hands[0];
+            let dealer_blackjack = result.dealer_hand.get_point_value() == 21 && result.dealer_hand.get_num_cards() == 2;
+            let player_blackjack = hand_result.hand.get_point_value() == 21 && hand_result.hand.get_num_cards() == 2;
+
+            if dealer_blackjack && !player_blackjack
+            {
+                assert_eq!(hand_result.outcome, outcome::Outcome::DealerBlackjack);
+                assert_eq!(hand_result.net, -1.0);
+            }
+            else if !player_blackjack
+            {
+                assert_eq!(hand_result.outcome, outcome::Outcome::Surrender);
+                assert_eq!(hand_result.net, -0.5);
+                assert_eq!(result.dealer_hand.get_num_cards(), 2);
+            }
+        }
+    }
+
+    #[test]
+    fn test_stacked_round_late_surrender_without_hole_card()
+    {
+        let rules = rules::TableRules { surrender: rules::SurrenderRule::Late, ..rules::TableRules::european() };
+        let mut player: TestPlayer = BlackjackPlayer::new("Surrenders");
+        player.prefers = player::BlackjackAction::Surrender;
+
+        let mut table = stacked_table(rules, "TS KH 6C AD");
+        let result = table.play_round(&mut player).unwrap();
+        assert!(result.hands[0].hand.is_surrendered());
+        assert_eq!(result.hands[0].outcome, outcome::Outcome::DealerBlackjack);
+        assert_eq!(result.hands[0].net, -1.0);
+
+        let mut table = stacked_table(rules, "TS KH 6C 9D");
+        let result = table.play_round(&mut player).unwrap();
+        assert_eq!(result.hands[0].outcome, outcome::Outcome::Surrender);
+        assert_eq!(result.hands[0].net, -0.5);
+    }
+
+    #[test]
+    fn test_play_round_early_surrender()
+    {
+        let mut table = Table::new();
+        let mut player: TestPlayer = BlackjackPlayer::new("Surrenders");
+        player.prefers = player::BlackjackAction::Surrender;
+        table.rules.surrender = rules::SurrenderRule::Early;
+
+        for _ in 0..200
+        {
+            let result = table.play_round(&mut player).unwrap();
+            let hand_result = &result.hands[0];
+            let player_blackjack = hand_result.hand.get_point_value() == 21 && hand_result.hand.get_num_cards() == 2;
+
+            if !player_blackjack
+            {
+                assert_eq!(hand_result.outcome, outcome::Outcome::Surrender);
+                assert_eq!(hand_result.net, -0.5);
+            }
+        }
+    }
+
+    #[test]
+    fn test_play_round_insurance()
+    {
+        let mut table = Table::new();
+        let mut player: TestPlayer = BlackjackPlayer::new("Insures");
+        player.insures = true;
+
+        let mut insured_rounds = 0;
+        for _ in 0..500
+        {
+            let result = table.play_round(&mut player).unwrap();
+            let dealer_blackjack = result.dealer_hand.get_point_value() == 21 && result.dealer_hand.get_num_cards() == 2;
+            let up_card_ace = result.dealer_hand.cards()[0].rank == rank::Rank::Ace;
+
+            match result.insurance
+            {
+                Some(net) => {
+                    insured_rounds += 1;
+                    assert!(up_card_ace);
+                    assert_eq!(net, if dealer_blackjack { 1.0 } else { -0.5 });
+                    if result.hands[0].outcome == outcome::Outcome::PlayerBlackjack || (dealer_blackjack && result.hands[0].outcome == outcome::Outcome::Push)
+                    {
+                        assert_eq!(result.hands[0].net + net, 1.0);
+                    }
+                }
+                None => assert!(!up_card_ace),
+            }
+        }
+        assert!(insured_rounds > 0);
+    }
+
+    #[test]
+    fn test_play_round_no_insurance()
+    {
+        let mut table = Table::new();
+        let mut player: TestPlayer = BlackjackPlayer::new("Declines");
+
+        for _ in 0..100
+        {
+            assert_eq!(table.play_round(&mut player).unwrap().insurance, None);
+        }
+    }
+
+    #[test]
+    fn test_play_round_no_hole_card()
+    {
+        let mut table = Table::with_rules(rules::TableRules::european());
+        let mut player: TestPlayer = BlackjackPlayer::new("Doubles");
+        player.prefers = player::BlackjackAction::Double;
+
+        let mut dealer_blackjacks = 0;
+        for _ in 0..1000
+        {
+            let result = table.play_round(&mut player).unwrap();
+            let hand_result = &result.hands[0];
+            let dealer_blackjack = result.dealer_hand.get_point_value() == 21 && result.dealer_hand.get_num_cards() == 2;
+            let player_blackjack = hand_result.hand.get_point_value() == 21 && hand_result.hand.get_num_cards() == 2;
+
+            if dealer_blackjack && !player_blackjack
+            {
+                dealer_blackjacks += 1;
+                assert_eq!(hand_result.outcome, outcome::Outcome::DealerBlackjack);
+                if hand_result.hand.is_doubled()
+                {
+                    assert_eq!(hand_result.net, -2.0);
+                }
+            }
+        }
+        assert!(dealer_blackjacks > 0);
+    }
+
+    #[test]
+    fn test_play_round_blackjack_payout()
+    {
+        let six_to_five = rules::TableRules { blackjack_payout: rules::BlackjackPayout::SixToFive, ..rules::TableRules::default() };
+        let mut table = Table::with_rules(six_to_five);
+        let mut player: TestPlayer = BlackjackPlayer::new("Stays");
+
+        for _ in 0..500
+        {
+            let result = table.play_round(&mut player).unwrap();
+            if result.hands[0].outcome == outcome::Outcome::PlayerBlackjack
+            {
+                assert_eq!(result.hands[0].net, 1.2);
+            }
+        }
+    }
+
+    #[test]
+    fn test_play_round_reshuffles_discards_when_shoe_runs_dry()
+    {
+        let mut table = Table::with_shoe(rules::TableRules::default(), shoe::Shoe::new(1, 1.0, 0));
+        let mut player: TestPlayer = BlackjackPlayer::new("Hits");
+        player.prefers = player::BlackjackAction::Hit;
+
+        for _ in 0..500
+        {
+            let result = table.play_round(&mut player).unwrap();
+            let cards_in_play = result.hands[0].hand.get_num_cards() + result.dealer_hand.get_num_cards();
+
+            assert!(cards_in_play >= 4);
+            assert_eq!(table.shoe().cards_remaining() + table.shoe().cards_discarded(), 52);
+        }
+    }
+
+    #[test]
+    fn test_play_round_with_seed_is_reproducible()
+    {
+        let mut first_table = Table::with_seed(rules::TableRules::default(), 99);
+        let mut second_table = Table::with_seed(rules::TableRules::default(), 99);
+        let mut first_player = TestPlayer::new("First");
+        let mut second_player = TestPlayer::new("Second");
+        assert_eq!(first_table.shoe().seed(), 99);
+
+        for _ in 0..200
+        {
+            let first_result = first_table.play_round(&mut first_player).unwrap();
+            let second_result = second_table.play_round(&mut second_player).unwrap();
+            assert_eq!(first_result.dealer_hand.cards(), second_result.dealer_hand.cards());
+            assert_eq!(first_result.hands.len(), second_result.hands.len());
+            for (first_hand, second_hand) in first_result.hands.iter().zip(second_result.hands.iter())
+            {
+                assert_eq!(first_hand.hand.cards(), second_hand.hand.cards());
+                assert_eq!(first_hand.outcome, second_hand.outcome);
+            }
+        }
+    }
+
+    #[test]
+    fn test_stacked_round_player_blackjack()
+    {
+        let mut table = stacked_table(rules::TableRules::default(), "AS 9H KD 7C");
+        let mut player: TestPlayer = BlackjackPlayer::new("Stays");
+
+        let result = table.play_round(&mut player).unwrap();
+        assert_eq!(result.hands[0].outcome, outcome::Outcome::PlayerBlackjack);
+        assert_eq!(result.hands[0].net, 1.5);
+        assert_eq!(result.dealer_hand.get_num_cards(), 2);
+    }
+
+    #[test]
+    fn test_stacked_round_dealer_blackjack()
+    {
+        let mut table = stacked_table(rules::TableRules::default(), "TS AH 9H KD");
+        let mut player: TestPlayer = BlackjackPlayer::new("Hits");
+        player.prefers = player::BlackjackAction::Hit;
+
+        let result = table.play_round(&mut player).unwrap();
+        assert_eq!(result.hands[0].outcome, outcome::Outcome::DealerBlackjack);
+        assert_eq!(result.hands[0].net, -1.0);
+        assert_eq!(result.hands[0].hand.get_num_cards(), 2);
+        assert_eq!(result.insurance, None);
+    }
+
+    #[test]
+    fn test_stacked_round_even_money_six_to_five()
+    {
+        let rules = rules::TableRules { blackjack_payout: rules::BlackjackPayout::SixToFive, ..rules::TableRules::default() };
+        let mut player: TestPlayer = BlackjackPlayer::new("Insures");
+        player.insures = true;
+
+        let mut table = stacked_table(rules, "AS AH KD 7C");
+        let result = table.play_round(&mut player).unwrap();
+        assert_eq!(result.hands[0].outcome, outcome::Outcome::PlayerBlackjack);
+        assert!((result.hands[0].net + result.insurance.unwrap() - 1.0).abs() < 1e-12);
+
+        let mut table = stacked_table(rules, "AS AH KD QC");
+        let result = table.play_round(&mut player).unwrap();
+        assert_eq!(result.hands[0].outcome, outcome::Outcome::Push);
+        assert_eq!(result.hands[0].net + result.insurance.unwrap(), 1.0);
+    }
+
+    #[test]
+    fn test_stacked_round_both_blackjack_push()
+    {
+        let mut table = stacked_table(rules::TableRules::default(), "AS AH KD QC");
+        let mut player: TestPlayer = BlackjackPlayer::new("Stays");
+
+        let result = table.play_round(&mut player).unwrap();
+        assert_eq!(result.hands[0].outcome, outcome::Outcome::Push);
+        assert_eq!(result.hands[0].net, 0.0);
+    }
+
+    #[test]
+    fn test_stacked_round_player_bust()
+    {
+        let mut table = stacked_table(rules::TableRules::default(), "TS TH 6C 7D 9S");
+        let mut player: TestPlayer = BlackjackPlayer::new("Hits");
+        player.prefers = player::BlackjackAction::Hit;
+
+        let result = table.play_round(&mut player).unwrap();
+        assert_eq!(result.hands[0].outcome, outcome::Outcome::PlayerBust);
+        assert_eq!(result.hands[0].hand.get_point_value(), 25);
+        assert_eq!(result.hands[0].net, -1.0);
+        assert_eq!(result.dealer_hand.get_num_cards(), 2);
+    }
+
+    #[test]
+    fn test_stacked_round_dealer_draws_and_busts()
+    {
+        let mut table = stacked_table(rules::TableRules::default(), "TS 6H 8C TD 9S");
+        let mut player: TestPlayer = BlackjackPlayer::new("Stays");
+
+        let result = table.play_round(&mut player).unwrap();
+        assert_eq!(result.dealer_hand.get_num_cards(), 3);
+        assert_eq!(result.dealer_hand.get_point_value(), 25);
+        assert_eq!(result.hands[0].outcome, outcome::Outcome::DealerBust);
+        assert_eq!(result.hands[0].net, 1.0);
+    }
+
+    #[test]
+    fn test_stacked_round_dealer_draws_to_push()
+    {
+        let mut table = stacked_table(rules::TableRules::default(), "TS 5H 7C 2D TH");
+        let mut player: TestPlayer = BlackjackPlayer::new("Stays");
+
+        let result = table.play_round(&mut player).unwrap();
+        assert_eq!(result.dealer_hand.get_num_cards(), 3);
+        assert_eq!(result.hands[0].outcome, outcome::Outcome::Push);
+        assert_eq!(result.hands[0].net, 0.0);
+    }
+
+    #[test]
+    fn test_stacked_round_dealer_soft_17()
+    {
+        let mut player: TestPlayer = BlackjackPlayer::new("Stays");
+
+        let mut table = stacked_table(rules::TableRules::vegas_strip(), "TS AH 8C 6D 2S");
+        let result = table.play_round(&mut player).unwrap();
+        assert_eq!(result.dealer_hand.get_num_cards(), 2);
+        assert_eq!(result.hands[0].outcome, outcome::Outcome::PlayerWin);
+
+        let mut table = stacked_table(rules::TableRules::downtown(), "TS AH 8C 6D 2S");
+        let result = table.play_round(&mut player).unwrap();
+        assert_eq!(result.dealer_hand.get_num_cards(), 3);
+        assert_eq!(result.hands[0].outcome, outcome::Outcome::DealerWin);
+    }
+
+    #[test]
+    fn test_stacked_round_split()
+    {
+        let mut table = stacked_table(rules::TableRules::default(), "8S 9H 8C 7D 3H TD TC");
+        let mut player: TestPlayer = BlackjackPlayer::new("Splits");
+        player.prefers = player::BlackjackAction::Split;
+
+        let result = table.play_round(&mut player).unwrap();
+        assert_eq!(result.hands.len(), 2);
+        assert_eq!(result.hands[0].hand.get_point_value(), 11);
+        assert_eq!(result.hands[1].hand.get_point_value(), 18);
+        assert_eq!(result.dealer_hand.get_point_value(), 26);
+        for hand_result in result.hands
+        {
+            assert!(hand_result.hand.is_split());
+            assert_eq!(hand_result.outcome, outcome::Outcome::DealerBust);
+            assert_eq!(hand_result.net, 1.0);
+        }
+    }
+
+    #[test]
+    fn test_stacked_round_double()
+    {
+        let mut table = stacked_table(rules::TableRules::default(), "6S 5H 5C TD 9H 8C");
+        let mut player: TestPlayer = BlackjackPlayer::new("Doubles");
+        player.prefers = player::BlackjackAction::Double;
+
+        let result = table.play_round(&mut player).unwrap();
+        assert!(result.hands[0].hand.is_doubled());
+        assert_eq!(result.hands[0].hand.get_point_value(), 20);
+        assert_eq!(result.hands[0].outcome, outcome::Outcome::DealerBust);
+        assert_eq!(result.hands[0].net, 2.0);
+    }
+
+    #[test]
+    fn test_stacked_round_runs_out_of_cards()
+    {
+        let mut table = stacked_table(rules::TableRules::default(), "TS 6H 8C TD");
+        let mut player: TestPlayer = BlackjackPlayer::new("Stays");
+
+        assert!(table.play_round(&mut player).is_err());
+        assert_eq!(player.get_num_cards(), 0);
+        assert_eq!(table.dealer().get_num_cards(), 0);
+    }
+
+    #[test]
+    fn test_play_seats_deals_in_seat_order()
+    {
+        let mut table = stacked_table(rules::TableRules::default(), "2S 3S 4S 9H 5S 6S 7S 8H");
+        let mut first: TestPlayer = BlackjackPlayer::new("First");
+        let mut second: TestPlayer = BlackjackPlayer::new("Second");
+        let mut third: TestPlayer = BlackjackPlayer::new("Third");
+
+        let results = table.play_seats(&mut [&mut first, &mut second, &mut third]).unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].hands[0].hand.cards(), cards_of("2S 5S"));
+        assert_eq!(results[1].hands[0].hand.cards(), cards_of("3S 6S"));
+        assert_eq!(results[2].hands[0].hand.cards(), cards_of("4S 7S"));
+        for result in results
+        {
+            assert_eq!(result.dealer_hand.cards(), cards_of("9H 8H"));
+            assert_eq!(result.hands[0].outcome, outcome::Outcome::DealerWin);
+        }
+        assert_eq!(first.get_num_cards(), 0);
+        assert_eq!(third.get_num_cards(), 0);
+    }
+
+    #[test]
+    fn test_play_seats_settles_independently()
+    {
+        let mut table = stacked_table(rules::TableRules::default(), "AS TH TC 6D KS 6H 9C TS TD 2C");
+        let mut blackjack: TestPlayer = BlackjackPlayer::new("Blackjack");
+        let mut hitter: TestPlayer = BlackjackPlayer::new("Hits");
+        hitter.prefers = player::BlackjackAction::Hit;
+        let mut stayer: TestPlayer = BlackjackPlayer::new("Stays");
+
+        let results = table.play_seats(&mut [&mut blackjack, &mut hitter, &mut stayer]).unwrap();
+        assert_eq!(results[0].hands[0].outcome, outcome::Outcome::PlayerBlackjack);
+        assert_eq!(results[0].hands[0].net, 1.5);
+        assert_eq!(results[1].hands[0].outcome, outcome::Outcome::PlayerBust);
+        assert_eq!(results[1].hands[0].net, -1.0);
+        assert_eq!(results[2].hands[0].outcome, outcome::Outcome::PlayerWin);
+        assert_eq!(results[2].hands[0].net, 1.0);
+        assert_eq!(results[2].dealer_hand.cards(), cards_of("6D TS 2C"));
+    }
+
+    #[test]
+    fn test_play_seats_dealer_stands_when_no_live_hands()
+    {
+        let mut table = stacked_table(rules::TableRules::default(), "AS TH 6D KS 6H TS TD");
+        let mut blackjack: TestPlayer = BlackjackPlayer::new("Blackjack");
+        let mut hitter: TestPlayer = BlackjackPlayer::new("Hits");
+        hitter.prefers = player::BlackjackAction::Hit;
+
+        let results = table.play_seats(&mut [&mut blackjack, &mut hitter]).unwrap();
+        assert_eq!(results[0].hands[0].outcome, outcome::Outcome::PlayerBlackjack);
+        assert_eq!(results[1].hands[0].outcome, outcome::Outcome::PlayerBust);
+        assert_eq!(results[1].dealer_hand.get_num_cards(), 2);
+    }
+
+    #[test]
+    fn test_play_seats_dealer_blackjack_ends_every_seat()
+    {
+        let mut table = stacked_table(rules::TableRules::default(), "TS 5H AD 9S 6C KD");
+        let mut first: TestPlayer = BlackjackPlayer::new("Hits");
+        first.prefers = player::BlackjackAction::Hit;
+        let mut second: TestPlayer = BlackjackPlayer::new("Hits");
+        second.prefers = player::BlackjackAction::Hit;
+
+        let results = table.play_seats(&mut [&mut first, &mut second]).unwrap();
+        for result in results
+        {
+            assert_eq!(result.hands[0].hand.get_num_cards(), 2);
+            assert_eq!(result.hands[0].outcome, outcome::Outcome::DealerBlackjack);
+        }
+    }
+
+    #[test]
+    fn test_play_seats_limits()
+    {
+        let mut table = Table::new();
+        let mut players: Vec<TestPlayer> = (0..=MAX_SEATS).map(|_| BlackjackPlayer::new("Seat")).collect();
+
+        let mut seats: Vec<&mut dyn BlackjackPlayer> = players.iter_mut().map(|player| player as &mut dyn BlackjackPlayer).collect();
+        assert_eq!(table.play_seats(&mut seats).unwrap_err(), RoundError::TooManySeats(8));
+        assert_eq!(table.play_seats(&mut seats[..MAX_SEATS]).unwrap().len(), MAX_SEATS);
+        assert!(table.play_seats(&mut []).unwrap().is_empty());
+        assert_eq!(format!("{}", RoundError::TooManySeats(8)), "8 seats requested but the table only has 7");
+    }
+
+    #[test]
+    fn test_table_view_shows_visible_cards()
+    {
+        let mut table = stacked_table(rules::TableRules::default(), "TS 9C 5H TH 6S 7D 2C 9H");
+        let mut hitter: TestPlayer = BlackjackPlayer::new("Hits");
+        hitter.prefers = player::BlackjackAction::Hit;
+        let mut observer: Observer = BlackjackPlayer::new("Observer");
+
+        let results = table.play_seats(&mut [&mut hitter, &mut observer]).unwrap();
+        assert_eq!(results[0].hands[0].hand.cards(), cards_of("TS TH 2C"));
+        assert_eq!(results[1].dealer_hand.cards(), cards_of("5H 7D 9H"));
+
+        let views = observer.views.borrow();
+        assert_eq!(views.len(), 1);
+        let (up_card, visible_cards, cards_remaining, _) = &views[0];
+        assert_eq!(*up_card, Some("5H".parse().unwrap()));
+        assert_eq!(*visible_cards, cards_of("5H 9C 6S TS TH 2C"));
+        assert_eq!(*cards_remaining, 1);
+    }
+
+    #[test]
+    fn test_counter_sees_hole_card_after_round()
+    {
+        let mut table = stacked_table(rules::TableRules::default(), "TS 6H 8C 5D 9S 2C 3C");
+        let mut player: TestPlayer = BlackjackPlayer::new("Stays");
+        assert_eq!(table.counter().running_count(), 0.0);
+
+        let result = table.play_round(&mut player).unwrap();
+        assert_eq!(result.dealer_hand.cards(), cards_of("6H 5D 9S"));
+        assert_eq!(table.counter().running_count(), 1.0);
+        assert_eq!(table.counter().cards_seen(), 5);
+        assert_eq!(table.true_count(), 1.0 / (2.0 / 52.0));
+    }
+
+    #[test]
+    fn test_counter_hides_hole_card_during_play()
+    {
+        let mut table = stacked_table(rules::TableRules::default(), "TS 6H 5C 2D 9S 9C");
+        let mut observer: Observer = BlackjackPlayer::new("Observer");
+
+        table.play_round(&mut observer).unwrap();
+        let views = observer.views.borrow();
+        assert_eq!(views.len(), 1);
+        assert_eq!(views[0].3, 1.0);
+        assert_eq!(table.counter().running_count(), 2.0);
+        assert_eq!(table.counter().cards_seen(), 5);
+    }
+
+    #[test]
+    fn test_counter_recounts_table_cards_when_shoe_runs_dry()
+    {
+        let mut table = stacked_table(rules::TableRules::default(), "TS 9H KD 8C 2S 5H 3C 6D");
+        let mut player: TestPlayer = BlackjackPlayer::new("Stays");
+        table.play_round(&mut player).unwrap();
+        assert_eq!(table.counter().running_count(), -2.0);
+
+        let result = table.play_round(&mut player).unwrap();
+        assert_eq!(result.dealer_hand.cards(), cards_of("5H 6D TS"));
+        assert_eq!(table.counter().running_count(), 3.0);
+        assert_eq!(table.counter().cards_seen(), 5);
+    }
+
+    #[test]
+    fn test_counter_resets_on_shuffle()
+    {
+        let mut table = Table::with_seed(rules::TableRules::default(), 5);
+        let mut player: TestPlayer = BlackjackPlayer::new("Stays");
+
+        let mut cards_seen = 0;
+        for _ in 0..200
+        {
+            let shuffled = table.shoe().needs_shuffle();
+            let result = table.play_round(&mut player).unwrap();
+            let cards_dealt = result.hands[0].hand.get_num_cards() + result.dealer_hand.get_num_cards();
+            cards_seen = match shuffled
+            {
+                true => cards_dealt,
+                false => cards_seen + cards_dealt,
+            };
+            assert_eq!(table.counter().cards_seen(), cards_seen);
+        }
+    }
+
+    #[test]
+    fn test_unbalanced_counting_system()
+    {
+        let mut table = Table::with_seed(rules::TableRules::default(), 9);
+        table.set_counting_system(Box::new(count::TagTable::ko()));
+        assert_eq!(table.counter().system().name(), "KO");
+        assert_eq!(table.counter().running_count(), -20.0);
+
+        let mut player: TestPlayer = BlackjackPlayer::new("Stays");
+        table.play_round(&mut player).unwrap();
+        assert_eq!(table.counter().cards_seen() as usize + table.shoe().cards_remaining(), 311);
+    }
+}